@@ -1,10 +1,18 @@
+use crate::world::chunk::CHUNK_SIZE;
+use glfw::Key;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 pub use std::io::Write;
 use std::path::Path;
 
-/// Represents the game configuration, including window, camera, controls, and physics settings.
-#[derive(Debug, Serialize, Deserialize)]
+/// The command-line usage, printed for `--help` and invalid arguments.
+pub const USAGE: &str =
+    "Usage: cubix [--block-size <size>] [--render-distance <chunks>] [--seed <seed>]";
+
+/// Represents the game configuration, including window, camera, controls, physics, world, UI,
+/// and graphics settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Window settings
     pub window: WindowConfig,
@@ -14,10 +22,29 @@ pub struct Config {
     pub controls: ControlsConfig,
     /// Physics settings
     pub physics: PhysicsConfig,
+    /// World settings
+    pub world: WorldConfig,
+    /// UI settings
+    pub ui: UiConfig,
+    /// Graphics settings
+    pub graphics: GraphicsConfig,
+    /// The config file values of the settings overridden on the command line
+    #[serde(skip)]
+    overridden: ArgOverrides,
+}
+
+/// The config file values of the settings overridden on the command line, written back in
+/// their place when saving so the overrides only apply to the current run.
+#[derive(Debug, Clone, Default)]
+struct ArgOverrides {
+    block_size: Option<f32>,
+    render_distance: Option<i32>,
+    seed: Option<u32>,
 }
 
 /// Represents the window configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WindowConfig {
     pub width: u32,
     pub height: u32,
@@ -27,29 +54,349 @@ pub struct WindowConfig {
 }
 
 /// Represents the camera configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CameraConfig {
     pub sensitivity: f32,
     pub fov: f32,
-    pub near_plane: f32,
-    pub far_plane: f32,
     pub position_x: f32,
     pub position_y: f32,
     pub position_z: f32,
+    /// How long the camera takes to turn back to a level pitch, in seconds
+    pub pitch_reset_duration: f32,
 }
 
 /// Represents the controls configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ControlsConfig {
+    /// Whether the cursor is grabbed when the game starts
     pub cursor_locked: bool,
+    /// How the cursor is held while it is grabbed; toggle the grab with Tab
+    pub cursor_grab_mode: CursorGrabMode,
+    /// How far away blocks can be broken or placed, in blocks
+    pub reach_in_blocks: f32,
+    /// The shortest reach the scroll wheel can set, in blocks
+    pub min_reach_in_blocks: f32,
+    /// The longest reach the scroll wheel can set, in blocks
+    pub max_reach_in_blocks: f32,
+    /// How far away the targeted block is outlined, in blocks; blocks past the reach are
+    /// outlined in `ui.highlight_out_of_reach_color` but can't be edited
+    pub highlight_distance_in_blocks: f32,
+    /// Whether glass, water and other transparent blocks are looked through, so the block
+    /// behind them is targeted instead
+    pub target_through_transparent: bool,
+    /// Whether blocks can be placed on the terrain surface the view passes near, even when no
+    /// block is targeted, such as past the edge of a cliff
+    pub place_on_surface: bool,
+    /// Whether reach grows with `world.block_size`; when off, `reach_in_blocks` is used as
+    /// a distance in world units
+    pub scale_reach_with_block_size: bool,
+    /// The largest number of blocks the fill tool changes at once
+    pub max_fill_volume: u64,
+    /// Whether to record the player's movement to `replay_file` or play it back, for debugging
+    pub replay_mode: ReplayMode,
+    /// The file movement is recorded to and played back from
+    pub replay_file: String,
+    /// Whether pressing jump twice in quick succession toggles fly mode
+    pub double_tap_fly: bool,
+    /// The longest time between the two jump presses of a double tap, in seconds
+    pub double_tap_window: f32,
+    /// What the player is allowed to do: edit the world, fly, or pass through blocks
+    pub game_mode: GameMode,
+    /// Whether sprint is active while the key is held, or toggled on and off by pressing it
+    pub sprint_mode: SprintMode,
+    /// How fast the player flies horizontally, in world units per second
+    pub fly_speed: f32,
+    /// How fast the player rises and sinks while flying, in world units per second
+    pub fly_vertical_speed: f32,
+    /// How much sprinting multiplies both fly speeds
+    pub fly_sprint_multiplier: f32,
+    /// The key that selects the next block type of the palette, such as `"RightBracket"`
+    pub palette_next_key: Key,
+    /// The key that selects the previous block type of the palette
+    pub palette_previous_key: Key,
+}
+
+/// How the sprint key controls sprinting.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SprintMode {
+    /// The player sprints while the key is held.
+    Hold,
+    /// Pressing the key starts sprinting, which lasts until it's pressed again or the
+    /// player stops moving.
+    Toggle,
+}
+
+/// Decides which interactions are available to the player.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    /// Blocks can be broken and placed, and the player can fly.
+    Creative,
+    /// Blocks can be broken and placed, but the player always walks.
+    Survival,
+    /// The world can't be changed; the player walks and can still look at blocks.
+    Adventure,
+    /// The world can't be changed; the player always flies and passes through blocks.
+    Spectator,
+}
+
+impl GameMode {
+    /// Returns whether the player can break, place and fill blocks.
+    pub fn can_edit(self) -> bool {
+        matches!(self, GameMode::Creative | GameMode::Survival)
+    }
+
+    /// Returns whether the player can switch to fly mode.
+    pub fn can_fly(self) -> bool {
+        matches!(self, GameMode::Creative | GameMode::Spectator)
+    }
+
+    /// Returns whether the player collides with blocks.
+    pub fn has_collision(self) -> bool {
+        self != GameMode::Spectator
+    }
+}
+
+/// Whether player movement is being recorded or played back.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayMode {
+    /// Movement comes from the keyboard and mouse and isn't recorded.
+    Off,
+    /// Movement comes from the keyboard and mouse and is written to the replay file.
+    Record,
+    /// Movement is read from the replay file until it ends.
+    Playback,
+}
+
+/// How the cursor is held by the window while the game has it grabbed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorGrabMode {
+    /// The cursor is hidden and locked in place; mouse movement is unlimited.
+    Locked,
+    /// The cursor is hidden and kept inside the window by moving it back to the center.
+    Confined,
 }
 
 /// Represents the physics configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PhysicsConfig {
     pub gravity: f32,
     pub jump_force: f32,
     pub player_height: f32,
+    /// How far above the feet the camera sits; should be less than `player_height`
+    pub eye_height: f32,
+    /// Whether walking into a one-block step jumps onto it automatically
+    pub auto_jump: bool,
+}
+
+/// Represents the world configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    /// How many chunks to load around the player in each direction
+    pub render_distance: i32,
+    /// Maximum time per frame spent generating, lighting and meshing chunks, in milliseconds
+    pub chunk_budget_ms: f32,
+    /// The most chunk meshes uploaded to the GPU per frame, or `0` for no limit
+    pub max_mesh_uploads_per_frame: usize,
+    /// How many chunks nearer a chunk straight ahead of a moving player is treated as when
+    /// ordering chunk loads, or `0` to always load nearest first
+    pub prefetch_bias: f32,
+    /// The order chunks are loaded, lit and meshed in; `sorted` goes by coordinate so runs
+    /// produce the same world state frame by frame, for tests (with a `chunk_budget_ms`
+    /// large enough that the time budget never cuts a frame short)
+    pub load_order: LoadOrder,
+    /// The edge length of a block in world units
+    pub block_size: f32,
+    /// The directory edited chunks are saved to
+    pub save_directory: String,
+    /// Whether to save whole chunks or only the blocks that differ from generated terrain
+    pub save_format: SaveFormat,
+    /// Seconds between automatic saves, or `0` to only save on exit
+    pub autosave_interval: f32,
+    /// How many times per second flowing water spreads by one block
+    pub water_flow_speed: f32,
+    /// How far from a log leaves stay alive, in blocks along each axis
+    pub leaf_decay_radius: i32,
+    /// How long leaves without a nearby log take to decay, in seconds
+    pub leaf_decay_time: f32,
+    /// The most mobs wandering around the player at once, or `0` for none
+    pub max_mobs: usize,
+    /// How far from the player mobs are removed, in blocks
+    pub mob_despawn_distance: f32,
+    /// The seed of the terrain generator
+    pub seed: u32,
+    /// The most neighboring terrain columns may differ in height, in blocks, or `0` for no
+    /// limit; lower values smooth cliffs into slopes
+    pub max_height_step: u32,
+    /// Where the player spawns; `fixed` uses the camera position from the settings
+    pub spawn: SpawnMode,
+    /// The distance from the origin to the square world border along X and Z, in blocks, or
+    /// unset for an endless world
+    pub border: Option<u32>,
+}
+
+/// The order the chunk manager processes chunks in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadOrder {
+    /// Nearest chunks first, with chunks ahead of a moving player moved forward.
+    Nearest,
+    /// By chunk coordinate, X first and then Z, independent of the player.
+    Sorted,
+}
+
+/// The format edited chunks are saved in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveFormat {
+    /// Every block of the chunk is stored.
+    Full,
+    /// Only the blocks that differ from the generated terrain are stored, and the terrain is
+    /// regenerated on load.
+    Delta,
+}
+
+/// Where the player is placed when the game starts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnMode {
+    /// Spawn at the configured camera position, raised above the ground if it is buried.
+    Fixed,
+    /// Spawn on the surface at a point picked from the world seed.
+    RandomSurface,
+}
+
+/// Represents the UI configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// How many blocks the build grid reaches past the targeted cell in each direction
+    pub build_grid_extent: i32,
+    /// Seconds between world diagnostics printed to the console, or `0` to not print them
+    pub diagnostics_interval: f32,
+    /// Whether to log the world's work queues when several frames in a row are slow
+    pub frame_watchdog: bool,
+    /// How long a frame may take before the frame watchdog counts it as slow, in milliseconds
+    pub slow_frame_ms: f32,
+    /// The directory screenshots are saved to
+    pub screenshot_directory: String,
+    /// Whether the crosshair, highlight and other overlays are hidden in screenshots
+    pub screenshot_hide_hud: bool,
+    /// The opacity of the placement preview, from barely visible to solid (`1`)
+    pub ghost_opacity: f32,
+    /// The RGBA color of the outline around the targeted block
+    pub highlight_color: [f32; 4],
+    /// The RGBA color of the outline around a block that is too far away to edit
+    pub highlight_out_of_reach_color: [f32; 4],
+    /// The width of the outline around the targeted block, in pixels
+    pub highlight_thickness: f32,
+}
+
+/// Represents the graphics configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsConfig {
+    /// A preset for the settings that cost the most performance; `custom` keeps them as set
+    pub quality: QualityPreset,
+    /// Whether the camera bobs while walking; turn off if it causes motion sickness
+    pub view_bobbing: bool,
+    /// How far the camera bobs at walking speed, in world units
+    pub bob_amplitude: f32,
+    /// How many strides per second the bob follows at walking speed
+    pub bob_frequency: f32,
+    /// Whether breaking a block emits a burst of particles
+    pub particles: bool,
+    /// Multiplier for the number of particles per burst
+    pub particle_density: f32,
+    /// The maximum number of particles alive at once
+    pub max_particles: usize,
+    /// How much block edges are darkened on chunk meshes, from `0` (off) to `1`
+    pub outline_strength: f32,
+    /// Whether to also draw the legacy per-block wireframe edges
+    pub legacy_block_edges: bool,
+    /// The maximum number of blocks outlined per frame by the per-block edge pass
+    pub max_edge_blocks: usize,
+    /// Whether the downward faces of the bottom layer of the world are left out of chunk
+    /// meshes, since nothing below can see them
+    pub cull_world_bottom: bool,
+    /// Whether the corners of block faces are darkened by the blocks around them
+    pub ambient_occlusion: bool,
+    /// Whether blocks cast shadows from the sun
+    pub shadows: bool,
+    /// The width and height of the shadow map, in texels
+    pub shadow_map_resolution: u32,
+    /// How far from the player shadows are drawn, in blocks; may be less than the render distance
+    pub shadow_distance: f32,
+    /// Whether distant terrain fades into the sky color near the render distance
+    pub fog: bool,
+    /// The distance to the near clip plane, in world units; geometry closer than this is cut
+    pub near_plane: f32,
+    /// The distance to the far clip plane, in world units; raised as needed to reach the
+    /// farthest corner of the loaded chunks
+    pub far_plane: f32,
+    /// The number of samples per pixel for antialiasing, or `0` to disable it; applied on start
+    pub msaa_samples: u32,
+    /// The display gamma; values above `1` brighten dark areas, clamped to a usable range
+    pub gamma: f32,
+}
+
+/// The lowest gamma that can be set, below which the scene turns almost black.
+pub const MIN_GAMMA: f32 = 0.5;
+
+/// The highest gamma that can be set, above which the image washes out.
+pub const MAX_GAMMA: f32 = 2.5;
+
+impl GraphicsConfig {
+    /// Changes the gamma by the given amount, keeping it within the allowed range.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - How much to add to the gamma; negative values darken the scene.
+    pub fn adjust_gamma(&mut self, amount: f32) {
+        self.gamma = (self.gamma + amount).clamp(MIN_GAMMA, MAX_GAMMA);
+    }
+
+    /// Returns the gamma to render with, clamped to the allowed range.
+    pub fn effective_gamma(&self) -> f32 {
+        self.gamma.clamp(MIN_GAMMA, MAX_GAMMA)
+    }
+}
+
+/// A bundle of graphics settings trading detail for performance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityPreset {
+    /// Use the individual settings as they are.
+    Custom,
+    /// Short render distance without shadows, ambient occlusion or antialiasing, for weak
+    /// machines.
+    Low,
+    /// Moderate render distance with short-range shadows.
+    Medium,
+    /// Long render distance with far shadows and antialiasing.
+    High,
+}
+
+impl QualityPreset {
+    /// Returns the next preset from low to high, wrapping around to low.
+    ///
+    /// # Returns
+    ///
+    /// The preset after this one; `Custom` is followed by `Low`.
+    pub fn next(self) -> Self {
+        match self {
+            QualityPreset::Custom | QualityPreset::High => QualityPreset::Low,
+            QualityPreset::Low => QualityPreset::Medium,
+            QualityPreset::Medium => QualityPreset::High,
+        }
+    }
 }
 
 impl Default for Config {
@@ -65,24 +412,144 @@ impl Default for Config {
             camera: CameraConfig {
                 sensitivity: 0.1,
                 fov: 70.0,
-                near_plane: 0.1,
-                far_plane: 1000.0,
                 position_x: 8.0,
                 position_y: 8.0, // Start slightly above terrain
                 position_z: 8.0,
+                pitch_reset_duration: 0.25,
             },
             controls: ControlsConfig {
                 cursor_locked: true,
+                cursor_grab_mode: CursorGrabMode::Locked,
+                reach_in_blocks: 5.0,
+                min_reach_in_blocks: 2.0,
+                max_reach_in_blocks: 32.0,
+                highlight_distance_in_blocks: 5.0,
+                target_through_transparent: false,
+                place_on_surface: false,
+                scale_reach_with_block_size: true,
+                max_fill_volume: 32768,
+                replay_mode: ReplayMode::Off,
+                replay_file: String::from("replay.txt"),
+                double_tap_fly: false,
+                double_tap_window: 0.3,
+                game_mode: GameMode::Creative,
+                sprint_mode: SprintMode::Hold,
+                fly_speed: 8.0,
+                fly_vertical_speed: 8.0,
+                fly_sprint_multiplier: 2.0,
+                palette_next_key: Key::RightBracket,
+                palette_previous_key: Key::LeftBracket,
             },
             physics: PhysicsConfig {
                 gravity: 0.015,
                 jump_force: 0.2,
                 player_height: 1.8,
+                eye_height: 1.62,
+                auto_jump: false,
+            },
+            world: WorldConfig {
+                render_distance: 4,
+                chunk_budget_ms: 4.0,
+                max_mesh_uploads_per_frame: 8,
+                prefetch_bias: 2.0,
+                load_order: LoadOrder::Nearest,
+                block_size: 1.0,
+                save_directory: String::from("saves/world"),
+                save_format: SaveFormat::Delta,
+                autosave_interval: 30.0,
+                water_flow_speed: 4.0,
+                leaf_decay_radius: 4,
+                leaf_decay_time: 2.0,
+                max_mobs: 4,
+                mob_despawn_distance: 64.0,
+                seed: 0,
+                max_height_step: 0,
+                spawn: SpawnMode::Fixed,
+                border: None,
+            },
+            ui: UiConfig {
+                build_grid_extent: 4,
+                diagnostics_interval: 0.0,
+                frame_watchdog: false,
+                slow_frame_ms: 50.0,
+                screenshot_directory: String::from("screenshots"),
+                screenshot_hide_hud: true,
+                ghost_opacity: 0.4,
+                highlight_color: [1.0, 1.0, 1.0, 1.0],
+                highlight_out_of_reach_color: [1.0, 0.3, 0.3, 0.6],
+                highlight_thickness: 2.0,
             },
+            graphics: GraphicsConfig {
+                quality: QualityPreset::Custom,
+                view_bobbing: true,
+                bob_amplitude: 0.05,
+                bob_frequency: 1.8,
+                particles: true,
+                particle_density: 1.0,
+                max_particles: 512,
+                outline_strength: 0.35,
+                legacy_block_edges: false,
+                max_edge_blocks: 65536,
+                cull_world_bottom: true,
+                ambient_occlusion: true,
+                shadows: true,
+                shadow_map_resolution: 2048,
+                shadow_distance: 48.0,
+                fog: true,
+                near_plane: 0.1,
+                far_plane: 1000.0,
+                msaa_samples: 0,
+                gamma: 1.0,
+            },
+            overridden: ArgOverrides::default(),
         }
     }
 }
 
+// The section defaults come from the full default configuration, so settings missing from an
+// older config file fall back to the same values as a fresh one.
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Config::default().window
+    }
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Config::default().camera
+    }
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Config::default().controls
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Config::default().physics
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Config::default().world
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Config::default().ui
+    }
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Config::default().graphics
+    }
+}
+
 impl Config {
     /// Loads the configuration from `config.toml` if it exists, otherwise creates a default configuration.
     ///
@@ -115,11 +582,227 @@ impl Config {
         config
     }
 
+    /// Overwrites the settings bundled by the quality preset with the preset's values.
+    ///
+    /// Does nothing for the `custom` preset. The antialiasing samples only take effect when
+    /// the window is created, so they are left alone when switching presets in game.
+    ///
+    /// # Arguments
+    ///
+    /// * `at_startup` - Whether the window hasn't been created yet, so the preset's
+    ///   antialiasing samples are applied too.
+    pub fn apply_quality_preset(&mut self, at_startup: bool) {
+        // (render distance, shadows, shadow distance, fog, ambient occlusion, MSAA samples)
+        let (render_distance, shadows, shadow_distance, fog, ambient_occlusion, msaa_samples) =
+            match self.graphics.quality {
+                QualityPreset::Custom => return,
+                QualityPreset::Low => (2, false, 24.0, true, false, 0),
+                QualityPreset::Medium => (4, true, 32.0, true, true, 2),
+                QualityPreset::High => (8, true, 64.0, true, true, 4),
+            };
+
+        self.world.render_distance = render_distance;
+        self.graphics.shadows = shadows;
+        self.graphics.shadow_distance = shadow_distance;
+        self.graphics.fog = fog;
+        self.graphics.ambient_occlusion = ambient_occlusion;
+        if at_startup {
+            self.graphics.msaa_samples = msaa_samples;
+        }
+    }
+
+    /// Overrides settings with command-line arguments, which take precedence over the
+    /// config file.
+    ///
+    /// Arguments are given as `--name value` or `--name=value`. The chunk size is fixed when
+    /// the game is built (`CHUNK_SIZE`), so it can't be overridden. The overrides only apply
+    /// to this run: settings saved in game keep the config file values of the overridden
+    /// settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The command-line arguments, without the program name.
+    ///
+    /// # Returns
+    ///
+    /// An error describing the first invalid argument, if any.
+    pub fn apply_args(&mut self, args: impl IntoIterator<Item = String>) -> Result<(), String> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if !matches!(
+                name.as_str(),
+                "--block-size" | "--render-distance" | "--seed"
+            ) {
+                return Err(format!("Unknown argument {}", name));
+            }
+            let Some(value) = value.or_else(|| args.next()) else {
+                return Err(format!("Missing value for {}", name));
+            };
+            let invalid = || format!("Invalid value for {}: {}", name, value);
+
+            match name.as_str() {
+                "--block-size" => {
+                    let block_size: f32 = value.parse().map_err(|_| invalid())?;
+                    if !block_size.is_finite() || block_size <= 0.0 {
+                        return Err(invalid());
+                    }
+                    self.overridden
+                        .block_size
+                        .get_or_insert(self.world.block_size);
+                    self.world.block_size = block_size;
+                }
+                "--render-distance" => {
+                    let render_distance: i32 = value.parse().map_err(|_| invalid())?;
+                    if render_distance < 1 {
+                        return Err(invalid());
+                    }
+                    self.overridden
+                        .render_distance
+                        .get_or_insert(self.world.render_distance);
+                    self.world.render_distance = render_distance;
+                }
+                _ => {
+                    let seed = value.parse().map_err(|_| invalid())?;
+                    self.overridden.seed.get_or_insert(self.world.seed);
+                    self.world.seed = seed;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Logs a warning if the camera clip planes don't fit the render distance.
+    ///
+    /// A near plane at or past the far plane leaves nothing visible at all. A far plane
+    /// closer than the loaded chunks is raised to reach them, see `effective_far_plane`.
+    pub fn check_clip_planes(&self) {
+        let (near, far) = (self.graphics.near_plane, self.effective_far_plane());
+        if near <= 0.0 || near >= far {
+            eprintln!(
+                "Graphics near_plane ({}) must be positive and below far_plane ({})",
+                near, far
+            );
+        }
+
+        if self.graphics.far_plane < far {
+            eprintln!(
+                "Graphics far_plane ({}) is closer than the corners of the render distance; \
+                 using {} instead",
+                self.graphics.far_plane, far
+            );
+        }
+    }
+
+    /// Returns the distance to the far clip plane to render with, in world units.
+    ///
+    /// The configured far plane is raised to the horizontal distance of the farthest corner
+    /// of the render distance, so no loaded chunk is clipped away.
+    pub fn effective_far_plane(&self) -> f32 {
+        let corner_distance = self.world.render_distance as f32
+            * CHUNK_SIZE as f32
+            * std::f32::consts::SQRT_2
+            * self.world.block_size;
+        self.graphics.far_plane.max(corner_distance)
+    }
+
+    /// Returns the distance at which blocks can be broken or placed, in world units.
+    ///
+    /// # Arguments
+    ///
+    /// * `reach_in_blocks` - The player's current reach, in blocks.
+    pub fn reach_distance(&self, reach_in_blocks: f32) -> f32 {
+        if self.controls.scale_reach_with_block_size {
+            reach_in_blocks * self.world.block_size
+        } else {
+            reach_in_blocks
+        }
+    }
+
+    /// Returns the configuration as it is saved, with the settings overridden on the command
+    /// line set back to their config file values.
+    fn persisted(&self) -> Config {
+        let mut persisted = self.clone();
+        let overridden = &self.overridden;
+        if let Some(block_size) = overridden.block_size {
+            persisted.world.block_size = block_size;
+        }
+        if let Some(render_distance) = overridden.render_distance {
+            persisted.world.render_distance = render_distance;
+        }
+        if let Some(seed) = overridden.seed {
+            persisted.world.seed = seed;
+        }
+        persisted
+    }
+
     /// Saves the configuration to `config.toml`.
+    ///
+    /// Called whenever a setting is changed in game, so the change survives a restart.
+    /// Failures are logged and otherwise ignored, so the game keeps running with the
+    /// changed settings when the file can't be written.
     pub fn save(&self) {
-        let config_str = toml::to_string_pretty(self).expect("Failed to serialize config");
-        let mut file = File::create("config.toml").expect("Failed to create config file");
-        file.write_all(config_str.as_bytes())
-            .expect("Failed to write config file");
+        let config_str = match toml::to_string_pretty(&self.persisted()) {
+            Ok(config_str) => config_str,
+            Err(e) => {
+                eprintln!("Error serializing config: {}", e);
+                return;
+            }
+        };
+
+        let result =
+            File::create("config.toml").and_then(|mut file| file.write_all(config_str.as_bytes()));
+        if let Err(e) = result {
+            eprintln!("Error writing config file: {}; settings were not saved", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_use_defaults() {
+        let config: Config = toml::from_str("[window]\nwidth = 800\n").unwrap();
+        let defaults = Config::default();
+
+        assert_eq!(config.window.width, 800);
+        assert_eq!(config.window.height, defaults.window.height);
+        assert_eq!(config.world.seed, defaults.world.seed);
+        assert_eq!(config.graphics.quality, defaults.graphics.quality);
+    }
+
+    #[test]
+    fn saving_keeps_the_config_file_values_of_overrides() {
+        let mut config = Config::default();
+        let args = ["--seed", "7", "--render-distance=12"].map(String::from);
+        config.apply_args(args).unwrap();
+        config.graphics.gamma = 1.5;
+
+        let persisted = config.persisted();
+
+        assert_eq!(config.world.seed, 7);
+        assert_eq!(persisted.world.seed, Config::default().world.seed);
+        assert_eq!(persisted.world.render_distance, 4);
+        assert_eq!(persisted.graphics.gamma, 1.5);
+    }
+
+    #[test]
+    fn far_plane_reaches_the_corner_chunks() {
+        let mut config = Config::default();
+        config.graphics.far_plane = 10.0;
+        config.world.render_distance = 8;
+        config.world.block_size = 2.0;
+
+        let corner = 8.0 * CHUNK_SIZE as f32 * 2.0 * std::f32::consts::SQRT_2;
+        assert_eq!(config.effective_far_plane(), corner);
+
+        config.graphics.far_plane = 1000.0;
+        assert_eq!(config.effective_far_plane(), 1000.0);
     }
 }
@@ -1,11 +1,13 @@
-use crate::config::Config;
+use crate::config::{Config, ControlsConfig, CursorGrabMode};
 use crate::player::input::PlayerInput;
 use cgmath::Matrix4;
-use cgmath::{Deg, perspective};
-use gl;
-use glfw::{Action, Glfw, GlfwReceiver, Key, Window, WindowEvent};
+use glfw::{Action, CursorMode, Glfw, GlfwReceiver, Key, Window, WindowEvent};
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once the confined cursor fallback was logged, so it isn't repeated on every toggle.
+static CONFINE_FALLBACK_WARNED: AtomicBool = AtomicBool::new(false);
 
 /// Global mutable state for mouse handling, wrapped in thread-safe containers.
 static MOUSE_STATE: Lazy<Mutex<MouseState>> = Lazy::new(|| {
@@ -23,6 +25,47 @@ struct MouseState {
     first_mouse: bool,
 }
 
+/// Returns the grab mode that works on the current platform.
+///
+/// Confining the cursor needs the window to move the cursor, which Wayland doesn't allow
+/// (GLFW reports an error instead, which aborts the game), so Wayland sessions fall back
+/// to locking the cursor.
+fn effective_grab_mode(mode: CursorGrabMode) -> CursorGrabMode {
+    if mode == CursorGrabMode::Confined && std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if !CONFINE_FALLBACK_WARNED.swap(true, Ordering::Relaxed) {
+            eprintln!("Confined cursor is not supported on Wayland; locking the cursor instead");
+        }
+        return CursorGrabMode::Locked;
+    }
+    mode
+}
+
+/// Applies the configured cursor grab to the window.
+///
+/// # Arguments
+///
+/// * `window` - A mutable reference to the GLFW window.
+/// * `locked` - Whether the cursor is grabbed.
+/// * `controls` - The controls settings with the cursor grab mode.
+pub fn apply_cursor_grab(window: &mut Window, locked: bool, controls: &ControlsConfig) {
+    let mode = if !locked {
+        CursorMode::Normal
+    } else {
+        match effective_grab_mode(controls.cursor_grab_mode) {
+            CursorGrabMode::Locked => CursorMode::Disabled,
+            CursorGrabMode::Confined => {
+                let (width, height) = window.get_size();
+                window.set_cursor_pos(width as f64 / 2.0, height as f64 / 2.0);
+                CursorMode::Hidden
+            }
+        }
+    };
+    window.set_cursor_mode(mode);
+
+    // Don't turn the jump of the cursor between modes into camera movement
+    MOUSE_STATE.lock().unwrap().first_mouse = true;
+}
+
 /// Processes GLFW events and updates the game state accordingly.
 ///
 /// # Arguments
@@ -54,12 +97,8 @@ pub fn process_events(
                     gl::Viewport(0, 0, width, height);
                 }
                 // Recalculate projection matrix to maintain aspect ratio
-                new_projection = Some(perspective(
-                    Deg(config.camera.fov),
-                    width as f32 / height as f32,
-                    config.camera.near_plane,
-                    config.camera.far_plane,
-                ));
+                new_projection =
+                    Some(camera.projection_matrix(width as f32 / height.max(1) as f32, config));
             }
             WindowEvent::Key(key, _, action, _) => {
                 // Special keys handling
@@ -80,36 +119,34 @@ pub fn process_events(
 
                             // Switch to fullscreen using a direct approach
                             glfw.with_primary_monitor(|_, m| {
-                                if let Some(monitor) = m {
-                                    if let Some(video_mode) = monitor.get_video_mode() {
-                                        // Use video mode resolution for fullscreen
-                                        window.set_monitor(
-                                            glfw::WindowMode::FullScreen(monitor),
+                                if let Some(monitor) = m
+                                    && let Some(video_mode) = monitor.get_video_mode()
+                                {
+                                    // Use video mode resolution for fullscreen
+                                    window.set_monitor(
+                                        glfw::WindowMode::FullScreen(monitor),
+                                        0,
+                                        0,
+                                        video_mode.width,
+                                        video_mode.height,
+                                        Some(video_mode.refresh_rate),
+                                    );
+
+                                    // Force update viewport size
+                                    unsafe {
+                                        gl::Viewport(
                                             0,
                                             0,
-                                            video_mode.width,
-                                            video_mode.height,
-                                            Some(video_mode.refresh_rate),
+                                            video_mode.width as i32,
+                                            video_mode.height as i32,
                                         );
-
-                                        // Force update viewport size
-                                        unsafe {
-                                            gl::Viewport(
-                                                0,
-                                                0,
-                                                video_mode.width as i32,
-                                                video_mode.height as i32,
-                                            );
-                                        }
-
-                                        // Update projection for new resolution
-                                        new_projection = Some(perspective(
-                                            Deg(config.camera.fov),
-                                            video_mode.width as f32 / video_mode.height as f32,
-                                            config.camera.near_plane,
-                                            config.camera.far_plane,
-                                        ));
                                     }
+
+                                    // Update projection for new resolution
+                                    new_projection = Some(camera.projection_matrix(
+                                        video_mode.width as f32 / video_mode.height as f32,
+                                        config,
+                                    ));
                                 }
                             });
                         } else {
@@ -134,17 +171,58 @@ pub fn process_events(
                             }
 
                             // Update projection for restored window size
-                            new_projection = Some(perspective(
-                                Deg(config.camera.fov),
+                            new_projection = Some(camera.projection_matrix(
                                 config.window.width as f32 / config.window.height as f32,
-                                config.camera.near_plane,
-                                config.camera.far_plane,
+                                config,
                             ));
                         }
 
                         // Save config changes
                         config.save();
                     }
+                    (Key::Tab, Action::Press) => {
+                        // Grab or release the cursor, e.g. to move it out of the window
+                        player_input.cursor_locked = !player_input.cursor_locked;
+                        apply_cursor_grab(window, player_input.cursor_locked, &config.controls);
+                        // Releasing the cursor also drops the targeted block, so the highlight
+                        // and any breaking in progress are cleared on the next update
+                        if !player_input.cursor_locked {
+                            player_input.release_all();
+                        }
+                    }
+                    (Key::R, Action::Press) if player_input.cursor_locked => {
+                        // Level the view without changing the direction the player faces
+                        camera.reset_pitch();
+                    }
+                    (Key::F4, Action::Press) => {
+                        // Step through the quality presets; the preset's antialiasing is only
+                        // applied on the next start, since it needs a new window
+                        config.graphics.quality = config.graphics.quality.next();
+                        config.apply_quality_preset(false);
+                        config.check_clip_planes();
+                        config.save();
+                    }
+                    (Key::Minus | Key::Equal, Action::Press | Action::Repeat) => {
+                        // Darken or brighten the scene; takes effect on the next frame
+                        let step = if key == Key::Equal { 0.1 } else { -0.1 };
+                        config.graphics.adjust_gamma(step);
+                    }
+                    (Key::Minus | Key::Equal, Action::Release) => {
+                        // Save the gamma once the key is let go, not on every repeat
+                        config.save();
+                    }
+                    (Key::P, Action::Press) => {
+                        // Switch between perspective and orthographic projection
+                        camera.orthographic = !camera.orthographic;
+                        let (width, height) = window.get_framebuffer_size();
+                        new_projection = Some(
+                            camera.projection_matrix(width as f32 / height.max(1) as f32, config),
+                        );
+                    }
+                    _ if !player_input.cursor_locked => {
+                        // The cursor is released to use other windows or menus, so keys
+                        // mustn't fill, mirror or otherwise edit the world behind them
+                    }
                     _ => {
                         // Pass other keys to player input system
                         player_input.key_callback(key, action);
@@ -153,7 +231,7 @@ pub fn process_events(
             }
             WindowEvent::CursorPos(x_pos, y_pos) => {
                 // Only process mouse movement when cursor is locked
-                if !config.controls.cursor_locked {
+                if !player_input.cursor_locked {
                     continue;
                 }
 
@@ -173,19 +251,44 @@ pub fn process_events(
 
                 // Constrain maximum movement per frame
                 const MAX_MOVEMENT: f32 = 100.0;
-                x_offset = x_offset.max(-MAX_MOVEMENT).min(MAX_MOVEMENT);
-                y_offset = y_offset.max(-MAX_MOVEMENT).min(MAX_MOVEMENT);
+                x_offset = x_offset.clamp(-MAX_MOVEMENT, MAX_MOVEMENT);
+                y_offset = y_offset.clamp(-MAX_MOVEMENT, MAX_MOVEMENT);
 
                 mouse_state.last_x = x_pos;
                 mouse_state.last_y = y_pos;
 
+                // A confined cursor is moved back to the center, so it never reaches the
+                // window border. The camera follows the movement, not the cursor, so the
+                // crosshair and block targeting stay centered in both modes
+                if effective_grab_mode(config.controls.cursor_grab_mode) == CursorGrabMode::Confined
+                {
+                    let (width, height) = window.get_size();
+                    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+                    window.set_cursor_pos(center_x as f64, center_y as f64);
+                    mouse_state.last_x = center_x;
+                    mouse_state.last_y = center_y;
+                }
+
                 camera.process_mouse_movement(x_offset, y_offset, true, config.camera.sensitivity);
             }
-            WindowEvent::MouseButton(_button, _action, _) => {
-                if !config.controls.cursor_locked {
+            WindowEvent::Scroll(_, y_offset) if camera.orthographic => {
+                // Scrolling zooms the orthographic view
+                camera.zoom(y_offset as f32);
+                let (width, height) = window.get_framebuffer_size();
+                new_projection =
+                    Some(camera.projection_matrix(width as f32 / height.max(1) as f32, config));
+            }
+            WindowEvent::Scroll(_, y_offset) => {
+                if !player_input.cursor_locked {
+                    continue;
+                }
+                player_input.scroll_callback(y_offset as f32, config);
+            }
+            WindowEvent::MouseButton(button, action, _) => {
+                if !player_input.cursor_locked {
                     continue;
                 }
-                // Implementation will come later
+                player_input.mouse_button_callback(button, action);
             }
             _ => {}
         }
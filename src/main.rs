@@ -1,8 +1,7 @@
 extern crate gl;
 extern crate glfw;
 
-use cgmath::Matrix;
-use gl::types::*;
+use cgmath::{Point3, Vector3};
 use glfw::Context;
 use std::time::{Duration, Instant};
 
@@ -12,33 +11,87 @@ mod player;
 mod rendering;
 mod world;
 
-use crate::config::Config;
+use crate::config::{Config, USAGE};
 use crate::events::process_events;
+use crate::player::build_helper::{draw_build_grid, draw_reach_label, draw_target_label};
+use crate::player::fill_tool::FillTool;
 use crate::player::input::PlayerInput;
+use crate::player::interaction::BlockInteraction;
+use crate::player::mirror_tool::{draw_mirror_plane, update_mirror};
 use crate::player::movement::handle_movement_input; // Updated import
+use crate::player::replay::Replay;
+use crate::player::view_bob::ViewBob;
+use crate::rendering::hud::{draw_block_name, draw_chunk_stats, draw_mesh_heat};
+use crate::rendering::screenshot::save_screenshot;
+use crate::rendering::shader::Shader;
+use crate::world::border::WorldBorder;
+use crate::world::chunk::{CHUNK_SIZE, chunk_coords};
+use crate::world::diagnostics::FrameWatchdog;
+use crate::world::generation::random_seed;
 use crate::world::init::App;
 
+/// The color of the sky, which distant terrain fades into.
+const SKY_COLOR: [f32; 4] = [0.2, 0.3, 0.3, 1.0];
+
+/// Where fog starts, as a fraction of the render distance.
+const FOG_START: f32 = 0.6;
+
+/// The length of the axes drawn at the world origin, in blocks.
+const AXES_LENGTH: f32 = 16.0;
+
 /// The main entry point of the Cubix application.
 fn main() {
     // Load config
     let mut config = Config::load();
+    config.apply_quality_preset(true);
+
+    // Command-line overrides win over the config file and the quality preset
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(e) = config.apply_args(args) {
+        eprintln!("{}", e);
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    }
+    config.check_clip_planes();
 
     let mut app = App::new(&config);
 
     // Initialize player input system
     let mut player_input = PlayerInput::new(&config);
 
+    // Initialize block breaking and placing
+    let mut interaction = BlockInteraction::new();
+
+    // Initialize the two-corner fill tool
+    let mut fill_tool = FillTool::new();
+
+    // Initialize the walking camera bob
+    let mut view_bob = ViewBob::new();
+
+    // Record or play back player movement, if enabled
+    let mut replay = Replay::new(&config);
+
+    // Log what the world was doing during runs of slow frames, if enabled
+    let mut frame_watchdog = FrameWatchdog::new();
+
     // For calculating delta time
     let mut last_frame = Instant::now();
 
     unsafe {
         gl::Enable(gl::DEPTH_TEST);
+        if config.graphics.msaa_samples > 0 {
+            gl::Enable(gl::MULTISAMPLE);
+        }
 
         // Render loop
         while !app.window.should_close() {
             // Calculate delta time
             let current_frame = Instant::now();
-            let delta_time = current_frame.duration_since(last_frame).as_secs_f32();
+            let frame_time = current_frame.duration_since(last_frame).as_secs_f32();
             last_frame = current_frame;
 
             // Process events and update projection if needed
@@ -53,7 +106,28 @@ fn main() {
                 app.projection = new_projection;
             }
 
+            // Record this frame's input, or replace it with the recorded one
+            let delta_time = replay.process(&mut player_input, &mut app.camera, frame_time);
+
+            // Start over in a new world generated from a random seed
+            if player_input.regenerate_world() {
+                app.regenerate_world(&config, random_seed());
+                player_input.velocity = Vector3::new(0.0, 0.0, 0.0);
+                // Drop any break in progress and fill selection from the old world
+                player_input.release_all();
+                interaction = BlockInteraction::new();
+                fill_tool = FillTool::new();
+            }
+
+            // Load and mesh chunks around the player within the frame budget
+            app.chunk_manager
+                .update(&mut app.world, app.camera.position, delta_time, &config);
+
+            // Save edited chunks in the background
+            app.world_saver.update(&mut app.world, &config, delta_time);
+
             // Handle player movement with delta time
+            let previous_position = app.camera.position;
             handle_movement_input(
                 &app.window,
                 &mut app.camera,
@@ -63,30 +137,192 @@ fn main() {
                 &app.world, // Pass the world
             );
 
-            // Update the view matrix with new camera orientation
-            app.update_view_matrix();
+            // Break or place the block the player is looking at
+            interaction.update(
+                &app.camera,
+                &mut player_input,
+                &mut app.world,
+                &mut app.particles,
+                delta_time,
+                &config,
+            );
+            fill_tool.update(
+                interaction.target,
+                &mut player_input,
+                &mut app.world,
+                &config,
+            );
+            let player_block = {
+                let block = app.world.to_block_space(app.camera.position);
+                [
+                    block.x.floor() as i32,
+                    block.y.floor() as i32,
+                    block.z.floor() as i32,
+                ]
+            };
+            update_mirror(
+                interaction.target.map_or(player_block, |hit| hit.position),
+                &mut player_input,
+                &mut app.world,
+            );
+            app.particles.update(delta_time, app.world.block_size);
+
+            let feet = app.camera.feet_position(&config);
+            app.mobs.update(&app.world, feet, delta_time, &config);
+
+            // Update dynamic blocks such as spreading grass and flowing water
+            app.ticking_blocks
+                .update(&mut app.world, delta_time, &config);
+            // Watch the real frame time, not the one played back from a recording
+            frame_watchdog.check(
+                frame_time,
+                &app.chunk_manager.diagnostics,
+                app.ticking_blocks.active_count(),
+                &config,
+            );
+
+            // Level out the camera pitch after a reset
+            app.camera.update(delta_time, &config);
 
-            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            // Update the view matrix with new camera orientation and bob
+            view_bob.update(
+                app.camera.position - previous_position,
+                &player_input,
+                delta_time,
+                &config,
+            );
+            app.update_view_matrix(view_bob.offset(&app.camera, &config));
+
+            // Render the sun's view of the terrain before the main pass
+            app.shadow_map.render(
+                &app.world,
+                app.camera.position,
+                &config.graphics,
+                app.window.get_framebuffer_size(),
+            );
+
+            gl::ClearColor(SKY_COLOR[0], SKY_COLOR[1], SKY_COLOR[2], SKY_COLOR[3]);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
 
-            app.shader.use_program();
+            // Set up projection and view matrices for both shaders
+            for shader in [&app.chunk_shader, &app.shader] {
+                shader.use_program();
+                shader.set_mat4("projection", &app.projection);
+                shader.set_mat4("view", &app.view);
+            }
+            app.shadow_map.bind(&app.chunk_shader, &config.graphics);
+
+            // Hide the edge of the loaded terrain in fog
+            let fog_end = if config.graphics.fog {
+                config.world.render_distance as f32 * CHUNK_SIZE as f32 * app.world.block_size
+            } else {
+                0.0
+            };
+            for shader in [&app.chunk_shader, &app.shader] {
+                set_view_adjustments(shader, fog_end, config.graphics.effective_gamma());
+            }
 
-            // Set up projection matrix
-            let projection_location =
-                gl::GetUniformLocation(app.shader.id, b"projection\0".as_ptr() as *const GLchar);
-            gl::UniformMatrix4fv(
-                projection_location,
-                1,
-                gl::FALSE as GLboolean,
-                app.projection.as_ptr(),
+            app.world.draw(
+                &app.chunk_shader,
+                &app.shader,
+                app.glfw.get_time() as f32,
+                app.camera.position,
+                &config.graphics,
             );
+            app.mobs.draw(&app.shader, &app.world);
+
+            // Screenshots can leave out everything that isn't part of the world
+            let screenshot = player_input.take_screenshot();
+            let show_hud = !(screenshot && config.ui.screenshot_hide_hud);
+
+            if show_hud {
+                interaction.draw(&app.chunk_shader, &mut app.highlight, &app.world, &config);
+            }
+            app.particles.draw(&app.chunk_shader, &app.camera);
 
-            // Set up view matrix
-            let view_location =
-                gl::GetUniformLocation(app.shader.id, b"view\0".as_ptr() as *const GLchar);
-            gl::UniformMatrix4fv(view_location, 1, gl::FALSE as GLboolean, app.view.as_ptr());
+            let (width, height) = app.window.get_framebuffer_size();
+            let (width, height) = (width as f32, height.max(1) as f32);
 
-            app.world.draw(&app.shader, app.glfw.get_time() as f32);
+            draw_block_name(player_input.selected_block(), &mut app.text, width, height);
+
+            // Show the reach in build mode and while it's being adjusted, and the grid and
+            // coordinates of the targeted face in build mode
+            if player_input.build_grid || player_input.is_key_pressed(glfw::Key::LeftAlt) {
+                draw_reach_label(player_input.reach_in_blocks, &mut app.text, width, height);
+            }
+            if player_input.build_grid
+                && let Some(hit) = &interaction.target
+            {
+                draw_build_grid(
+                    hit,
+                    &app.world,
+                    &mut app.gizmos,
+                    config.ui.build_grid_extent,
+                );
+                draw_target_label(hit, &mut app.text, width, height);
+            }
+            fill_tool.draw(&mut app.highlight, &mut app.text, &config);
+            draw_mirror_plane(&app.world, app.camera.position, &mut app.gizmos);
+            if let Some(border) = WorldBorder::new(&config.world) {
+                border.draw(&app.world, app.camera.position, &mut app.gizmos);
+            }
+
+            // Show the world axes at the origin and at the player's feet
+            if player_input.show_axes {
+                let block_size = app.world.block_size;
+                app.gizmos
+                    .axes(Point3::new(0.0, 0.0, 0.0), AXES_LENGTH * block_size);
+                app.gizmos
+                    .axes(app.camera.feet_position(&config), block_size);
+            }
+
+            if player_input.mesh_heat {
+                draw_mesh_heat(&app.world, &mut app.gizmos);
+            }
+
+            // Show the mesh statistics of the targeted chunk, or the player's chunk
+            if player_input.chunk_stats {
+                let [x, _, z] = match &interaction.target {
+                    Some(hit) => hit.position,
+                    None => {
+                        let block = app.world.to_block_space(app.camera.position);
+                        [block.x.floor() as i32, 0, block.z.floor() as i32]
+                    }
+                };
+                draw_chunk_stats(
+                    &app.world,
+                    chunk_coords(x, z),
+                    &app.chunk_manager.diagnostics,
+                    &mut app.text,
+                );
+            }
+
+            if show_hud {
+                app.highlight.draw(&app.chunk_shader, &app.world, &config);
+                app.gizmos.draw(&app.chunk_shader);
+
+                // The HUD isn't part of the scene, so it isn't brightened, fogged or shadowed
+                for shader in [&app.chunk_shader, &app.shader] {
+                    set_view_adjustments(shader, 0.0, 1.0);
+                }
+                app.chunk_shader.set_float("shadowStrength", 0.0);
+
+                interaction.draw_break_progress(&mut app.text, width, height);
+                app.crosshair.draw(&app.shader, width / height);
+                app.text.draw(&app.chunk_shader, width, height);
+            } else {
+                app.highlight.clear();
+                app.gizmos.clear();
+                app.text.clear();
+            }
+
+            if screenshot {
+                let (width, height) = app.window.get_framebuffer_size();
+                match save_screenshot(width, height, &config.ui.screenshot_directory) {
+                    Ok(path) => println!("Saved screenshot to {}", path.display()),
+                    Err(e) => eprintln!("Failed to save screenshot: {}", e),
+                }
+            }
 
             app.window.swap_buffers();
             app.glfw.poll_events();
@@ -97,4 +333,22 @@ fn main() {
             }
         }
     }
+
+    // Make sure no edits are lost when exiting
+    app.world_saver.save_world_blocking(&mut app.world, &config);
+}
+
+/// Sets the fog and gamma uniforms of a shader, which apply to everything drawn with it.
+///
+/// # Arguments
+///
+/// * `shader` - The shader to set the uniforms of; it is left in use.
+/// * `fog_end` - The distance where fog fully hides the scene, or `0` for no fog.
+/// * `gamma` - The display gamma, or `1` to leave colors unchanged.
+fn set_view_adjustments(shader: &Shader, fog_end: f32, gamma: f32) {
+    shader.use_program();
+    shader.set_float("fogStart", fog_end * FOG_START);
+    shader.set_float("fogEnd", fog_end);
+    shader.set_vec4("fogColor", SKY_COLOR);
+    shader.set_float("gamma", gamma);
 }
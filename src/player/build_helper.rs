@@ -0,0 +1,110 @@
+use crate::rendering::gizmos::Gizmos;
+use crate::rendering::text::TextRenderer;
+use crate::world::raycast::BlockHit;
+use crate::world::world::World;
+use cgmath::Point3;
+
+/// The color of the build grid lines.
+const GRID_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.25];
+
+/// How far the grid is lifted off the targeted face, in blocks, to avoid z-fighting.
+const GRID_OFFSET: f32 = 0.01;
+
+/// The color of the target coordinate label.
+const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+
+/// The size of a font pixel in the target coordinate label, in screen pixels.
+const LABEL_SCALE: f32 = 2.0;
+
+/// Queues grid lines on the plane of the targeted face, centered on the cell the placement
+/// ghost would occupy.
+///
+/// # Arguments
+///
+/// * `hit` - The targeted block and face.
+/// * `world` - A reference to the game world.
+/// * `gizmos` - The gizmos to queue the grid lines on.
+/// * `extent` - How many blocks the grid reaches past the targeted cell in each direction.
+pub fn draw_build_grid(hit: &BlockHit, world: &World, gizmos: &mut Gizmos, extent: i32) {
+    // The ray started inside a block, so there is no face to align to
+    let Some(axis) = (0..3).find(|&axis| hit.normal[axis] != 0) else {
+        return;
+    };
+
+    // The plane through the targeted face, lifted slightly toward the viewer
+    let face = if hit.normal[axis] > 0 {
+        hit.shape.max[axis]
+    } else {
+        hit.shape.min[axis]
+    };
+    let plane = hit.position[axis] as f32 + face + hit.normal[axis] as f32 * GRID_OFFSET;
+
+    // The two axes spanning the plane
+    let (u_axis, v_axis) = match axis {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    };
+    let u_center = hit.position[u_axis];
+    let v_center = hit.position[v_axis];
+    let (u_min, u_max) = (u_center - extent, u_center + extent + 1);
+    let (v_min, v_max) = (v_center - extent, v_center + extent + 1);
+
+    let to_world = |u: i32, v: i32| {
+        let mut point = [0.0; 3];
+        point[axis] = plane;
+        point[u_axis] = u as f32;
+        point[v_axis] = v as f32;
+        Point3::new(point[0], point[1], point[2]) * world.block_size
+    };
+
+    for u in u_min..=u_max {
+        gizmos.line(to_world(u, v_min), to_world(u, v_max), GRID_COLOR);
+    }
+    for v in v_min..=v_max {
+        gizmos.line(to_world(u_min, v), to_world(u_max, v), GRID_COLOR);
+    }
+}
+
+/// Queues the coordinates and hit face of the targeted block just below and to the right of
+/// the crosshair.
+///
+/// # Arguments
+///
+/// * `hit` - The targeted block.
+/// * `text` - The text renderer to queue the label on.
+/// * `width` - The framebuffer width, in pixels.
+/// * `height` - The framebuffer height, in pixels.
+pub fn draw_target_label(hit: &BlockHit, text: &mut TextRenderer, width: f32, height: f32) {
+    let [x, y, z] = hit.position;
+    let label = match hit.face() {
+        Some(face) => format!("{} {} {} {}", x, y, z, face.label()),
+        None => format!("{} {} {}", x, y, z),
+    };
+    text.queue(
+        &label,
+        width / 2.0 + 16.0,
+        height / 2.0 + 16.0,
+        LABEL_SCALE,
+        LABEL_COLOR,
+    );
+}
+
+/// Queues the current reach below the target coordinate label.
+///
+/// # Arguments
+///
+/// * `reach_in_blocks` - The player's current reach, in blocks.
+/// * `text` - The text renderer to queue the label on.
+/// * `width` - The framebuffer width, in pixels.
+/// * `height` - The framebuffer height, in pixels.
+pub fn draw_reach_label(reach_in_blocks: f32, text: &mut TextRenderer, width: f32, height: f32) {
+    let label = format!("REACH {:.1}", reach_in_blocks);
+    text.queue(
+        &label,
+        width / 2.0 + 16.0,
+        height / 2.0 + 16.0 + 8.0 * LABEL_SCALE,
+        LABEL_SCALE,
+        LABEL_COLOR,
+    );
+}
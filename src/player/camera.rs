@@ -1,4 +1,11 @@
-use cgmath::{InnerSpace, Matrix4, Point3, Vector3};
+use crate::config::Config;
+use cgmath::{Deg, InnerSpace, Matrix4, Point3, Vector3, ortho, perspective};
+
+/// The default half height of the orthographic view, in blocks.
+const DEFAULT_ORTHO_ZOOM: f32 = 16.0;
+
+/// The smallest and largest half height of the orthographic view, in blocks.
+const ORTHO_ZOOM_RANGE: (f32, f32) = (2.0, 128.0);
 
 /// Represents the player's camera, including position and orientation.
 pub struct Camera {
@@ -7,6 +14,12 @@ pub struct Camera {
     pub up: Vector3<f32>,
     pub yaw: f32,   // Horizontal rotation (in degrees)
     pub pitch: f32, // Vertical rotation (in degrees)
+    /// Whether the camera uses an orthographic instead of a perspective projection.
+    pub orthographic: bool,
+    /// Half the height of the orthographic view, in blocks.
+    pub ortho_zoom: f32,
+    /// The pitch a running level-out started from, and the seconds since it started.
+    pitch_reset: Option<(f32, f32)>,
 }
 
 impl Camera {
@@ -26,16 +39,117 @@ impl Camera {
             up: Vector3::new(0.0, 1.0, 0.0),
             yaw: -90.0, // Start facing negative z
             pitch: 0.0,
+            orthographic: false,
+            ortho_zoom: DEFAULT_ORTHO_ZOOM,
+            pitch_reset: None,
         }
     }
 
+    /// Returns the position of the player's feet, below the camera by the eye height.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the game configuration.
+    ///
+    /// # Returns
+    ///
+    /// The feet position, in world units.
+    pub fn feet_position(&self, config: &Config) -> Point3<f32> {
+        Point3::new(
+            self.position.x,
+            self.position.y - config.physics.eye_height,
+            self.position.z,
+        )
+    }
+
     /// Returns the view matrix for the camera.
     ///
+    /// # Arguments
+    ///
+    /// * `offset` - An offset added to the eye position, such as view bobbing.
+    ///
     /// # Returns
     ///
     /// The view matrix.
-    pub fn get_view_matrix(&self) -> Matrix4<f32> {
-        Matrix4::look_at_rh(self.position, self.position + self.front, self.up)
+    pub fn get_view_matrix(&self, offset: Vector3<f32>) -> Matrix4<f32> {
+        let eye = self.position + offset;
+        Matrix4::look_at_rh(eye, eye + self.front, self.up)
+    }
+
+    /// Returns the projection matrix for the camera.
+    ///
+    /// Rays cast from the screen center still start at the camera position and follow
+    /// `front` in both projections, so block targeting works the same in both.
+    ///
+    /// # Arguments
+    ///
+    /// * `aspect_ratio` - The width of the viewport divided by its height.
+    /// * `config` - A reference to the game configuration.
+    ///
+    /// # Returns
+    ///
+    /// The perspective or orthographic projection matrix.
+    pub fn projection_matrix(&self, aspect_ratio: f32, config: &Config) -> Matrix4<f32> {
+        if self.orthographic {
+            let half_height = self.ortho_zoom * config.world.block_size;
+            let half_width = half_height * aspect_ratio;
+            ortho(
+                -half_width,
+                half_width,
+                -half_height,
+                half_height,
+                config.graphics.near_plane,
+                config.effective_far_plane(),
+            )
+        } else {
+            perspective(
+                Deg(config.camera.fov),
+                aspect_ratio,
+                config.graphics.near_plane,
+                config.effective_far_plane(),
+            )
+        }
+    }
+
+    /// Zooms the orthographic view in or out.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The number of scroll steps; positive values zoom in.
+    pub fn zoom(&mut self, steps: f32) {
+        self.ortho_zoom =
+            (self.ortho_zoom * 0.9_f32.powf(steps)).clamp(ORTHO_ZOOM_RANGE.0, ORTHO_ZOOM_RANGE.1);
+    }
+
+    /// Starts smoothly turning the camera back to a level pitch, keeping the yaw.
+    pub fn reset_pitch(&mut self) {
+        self.pitch_reset = Some((self.pitch, 0.0));
+    }
+
+    /// Advances a running pitch reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(&mut self, delta_time: f32, config: &Config) {
+        let Some((start_pitch, elapsed)) = self.pitch_reset else {
+            return;
+        };
+
+        let elapsed = elapsed + delta_time;
+        let duration = config.camera.pitch_reset_duration;
+        let progress = if duration > 0.0 {
+            (elapsed / duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        // Ease in and out, so the turn doesn't start or stop abruptly
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+        self.pitch = start_pitch * (1.0 - eased);
+        self.pitch_reset = (progress < 1.0).then_some((start_pitch, elapsed));
+        self.update_camera_vectors();
     }
 
     /// Processes mouse movement to update the camera's orientation.
@@ -56,14 +170,14 @@ impl Camera {
         self.yaw += x_offset * sensitivity;
         self.pitch += y_offset * sensitivity;
 
+        // Looking up or down takes over from a running pitch reset
+        if y_offset != 0.0 {
+            self.pitch_reset = None;
+        }
+
         // Constrain pitch to avoid flipping
         if constrain_pitch {
-            if self.pitch > 89.0 {
-                self.pitch = 89.0;
-            }
-            if self.pitch < -89.0 {
-                self.pitch = -89.0;
-            }
+            self.pitch = self.pitch.clamp(-89.0, 89.0);
         }
 
         // Update front vector
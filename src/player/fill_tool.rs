@@ -0,0 +1,160 @@
+use crate::config::Config;
+use crate::player::input::PlayerInput;
+use crate::rendering::highlight::BlockHighlight;
+use crate::rendering::text::TextRenderer;
+use crate::world::raycast::BlockHit;
+use crate::world::world::World;
+
+/// The color of the selection outline.
+const SELECTION_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 0.9];
+
+/// The color of the selection label when the selection is too large to fill.
+const TOO_LARGE_COLOR: [f32; 4] = [1.0, 0.3, 0.3, 0.9];
+
+/// The size of a font pixel in the selection label, in screen pixels.
+const LABEL_SCALE: f32 = 2.0;
+
+/// The actions of the fill tool, triggered from the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillAction {
+    /// Sets the next corner of the selection to the targeted block.
+    SetCorner,
+    /// Fills the selection with the selected block.
+    Fill,
+    /// Replaces every block in the selection with air.
+    Clear,
+}
+
+/// Fills or clears a box of blocks selected by two corners.
+///
+/// The first corner is set at the targeted block, the second corner at the next targeted
+/// block, and setting a corner again starts a new selection.
+pub struct FillTool {
+    corner_a: Option<[i32; 3]>,
+    corner_b: Option<[i32; 3]>,
+}
+
+impl FillTool {
+    /// Creates a new `FillTool` with no selection.
+    ///
+    /// # Returns
+    ///
+    /// A new `FillTool` instance.
+    pub fn new() -> Self {
+        FillTool {
+            corner_a: None,
+            corner_b: None,
+        }
+    }
+
+    /// Applies the pending fill tool action, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The block the player is looking at.
+    /// * `player_input` - A mutable reference to the `PlayerInput` instance.
+    /// * `world` - A mutable reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(
+        &mut self,
+        target: Option<BlockHit>,
+        player_input: &mut PlayerInput,
+        world: &mut World,
+        config: &Config,
+    ) {
+        let Some(action) = player_input.fill_action() else {
+            return;
+        };
+        if !config.controls.game_mode.can_edit() {
+            return;
+        }
+
+        match action {
+            FillAction::SetCorner => {
+                let Some(hit) = target else {
+                    return;
+                };
+                if self.corner_a.is_none() || self.corner_b.is_some() {
+                    self.corner_a = Some(hit.position);
+                    self.corner_b = None;
+                } else {
+                    self.corner_b = Some(hit.position);
+                }
+            }
+            FillAction::Fill => self.fill(world, player_input.selected_block(), config),
+            FillAction::Clear => self.fill(world, 0, config),
+        }
+    }
+
+    /// Returns the minimum and maximum corner of the selection, in block coordinates.
+    fn bounds(&self) -> Option<([i32; 3], [i32; 3])> {
+        let (a, b) = (self.corner_a?, self.corner_b?);
+        Some((
+            [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])],
+            [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])],
+        ))
+    }
+
+    /// Returns the number of blocks in the selection.
+    fn volume(min: [i32; 3], max: [i32; 3]) -> u64 {
+        (0..3)
+            .map(|axis| (max[axis] - min[axis] + 1) as u64)
+            .product()
+    }
+
+    /// Sets every block in the selection to the given block id.
+    fn fill(&self, world: &mut World, block_id: u32, config: &Config) {
+        let Some((min, max)) = self.bounds() else {
+            return;
+        };
+
+        // Refuse huge fills instead of freezing the game
+        let volume = Self::volume(min, max);
+        if volume > config.controls.max_fill_volume {
+            eprintln!(
+                "Selection of {} blocks exceeds the fill limit of {}",
+                volume, config.controls.max_fill_volume
+            );
+            return;
+        }
+
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    world.edit_block(x, y, z, block_id);
+                }
+            }
+        }
+    }
+
+    /// Queues the selection outline and a label with its size.
+    ///
+    /// # Arguments
+    ///
+    /// * `highlight` - The block highlight to queue the outline on.
+    /// * `text` - The text renderer to queue the label on.
+    /// * `config` - A reference to the game configuration.
+    pub fn draw(&self, highlight: &mut BlockHighlight, text: &mut TextRenderer, config: &Config) {
+        // Show the first corner on its own until the second one is set
+        let Some(corner_a) = self.corner_a else {
+            return;
+        };
+        let (min, max) = self.bounds().unwrap_or((corner_a, corner_a));
+        highlight.region(min, max, SELECTION_COLOR);
+
+        let size = [0, 1, 2].map(|axis| max[axis] - min[axis] + 1);
+        let volume = Self::volume(min, max);
+        let color = if volume > config.controls.max_fill_volume {
+            TOO_LARGE_COLOR
+        } else {
+            SELECTION_COLOR
+        };
+        text.queue(
+            &format!("FILL {}X{}X{} ({})", size[0], size[1], size[2], volume),
+            16.0,
+            16.0,
+            LABEL_SCALE,
+            color,
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use crate::config::Config;
-use glfw::{Action, Key};
+use crate::config::{Config, SprintMode};
+use crate::player::fill_tool::FillAction;
+use glfw::{Action, Key, MouseButton};
 use std::collections::HashMap;
 
 /// Manages player input, including movement and key states.
@@ -7,14 +8,78 @@ pub struct PlayerInput {
     key_states: HashMap<Key, bool>,
     pub movement_speed: f32,
     pub fly_mode: bool,
+    pub build_grid: bool,
+    /// Whether the cursor is grabbed by the window; toggled with Tab, but never saved.
+    pub cursor_locked: bool,
+    pub chunk_stats: bool,
+    /// Whether the coordinate axes are drawn at the origin and at the player.
+    pub show_axes: bool,
+    /// Whether chunks are outlined by how often they were re-meshed recently.
+    pub mesh_heat: bool,
     pub gravity: f32,
     pub jump_force: f32,
     pub velocity: cgmath::Vector3<f32>,
     pub on_ground: bool,
     pub last_jump_time: f32,
     pub jump_cooldown: f32,
+    /// The index of the selected block type in `PALETTE`.
+    pub palette_index: usize,
+    /// The current reach, in blocks, adjustable with Left Alt and the scroll wheel.
+    pub reach_in_blocks: f32,
+    /// Whether double-tapping jump toggles fly mode, and the longest gap between the taps.
+    double_tap_fly: Option<f32>,
+    /// The simulated time, advanced by the frame time, so double taps replay exactly.
+    clock: f32,
+    /// When jump was last pressed on `clock`, for detecting double taps.
+    last_jump_press: Option<f32>,
+    /// The keys that select the next and the previous block type of the palette.
+    palette_keys: (Key, Key),
+    /// Whether the player is sprinting, updated each frame by `update_sprint`.
+    sprinting: bool,
+    /// Whether the sprint key was held on the previous frame, for detecting presses.
+    sprint_key_held: bool,
+    place_requested: bool,
+    breaking: bool,
+    fill_requested: Option<FillAction>,
+    mirror_requested: bool,
+    screenshot_requested: bool,
+    regenerate_requested: bool,
 }
 
+/// The physics and movement state of the player that carries over between frames, saved
+/// when a replay starts recording so playback starts from the same state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotionState {
+    pub velocity: cgmath::Vector3<f32>,
+    pub on_ground: bool,
+    pub last_jump_time: f32,
+    pub fly_mode: bool,
+    pub sprinting: bool,
+    pub sprint_key_held: bool,
+}
+
+/// Every block type that can be placed, in the order the palette keys and the scroll wheel
+/// cycle through them.
+///
+/// The first ten can also be selected directly with the number keys; the rest can only be
+/// reached by cycling.
+pub const PALETTE: [u32; 11] = [
+    1,  // Dirt
+    2,  // Grass
+    3,  // Stone
+    4,  // Snow
+    5,  // Water
+    6,  // Log
+    9,  // Stone slab
+    10, // Torch
+    18, // Glass
+    19, // Tall grass
+    20, // Leaves
+];
+
+/// How much one scroll step changes the reach, in blocks.
+const REACH_STEP: f32 = 0.5;
+
 impl PlayerInput {
     /// Creates a new `PlayerInput` instance with the given configuration.
     ///
@@ -30,12 +95,37 @@ impl PlayerInput {
             key_states: HashMap::new(),
             movement_speed: 5.0,
             fly_mode: false,
+            build_grid: false,
+            cursor_locked: config.controls.cursor_locked,
+            chunk_stats: false,
+            show_axes: false,
+            mesh_heat: false,
             gravity: config.physics.gravity,
             jump_force: config.physics.jump_force,
             velocity: cgmath::Vector3::new(0.0, 0.0, 0.0),
             on_ground: false,
             last_jump_time: 0.0,
             jump_cooldown: 0.2, // Prevent jump spam
+            palette_index: 0,
+            reach_in_blocks: config.controls.reach_in_blocks,
+            double_tap_fly: config
+                .controls
+                .double_tap_fly
+                .then_some(config.controls.double_tap_window),
+            clock: 0.0,
+            last_jump_press: None,
+            palette_keys: (
+                config.controls.palette_next_key,
+                config.controls.palette_previous_key,
+            ),
+            sprinting: false,
+            sprint_key_held: false,
+            place_requested: false,
+            breaking: false,
+            fill_requested: None,
+            mirror_requested: false,
+            screenshot_requested: false,
+            regenerate_requested: false,
         }
     }
 
@@ -52,16 +142,119 @@ impl PlayerInput {
 
                 // Toggle fly mode with F key
                 if key == Key::F {
-                    self.fly_mode = !self.fly_mode;
-                    if self.fly_mode {
-                        self.velocity.y = 0.0; // Reset vertical velocity when entering fly mode
+                    self.toggle_fly_mode();
+                }
+
+                // Or by double-tapping jump, if enabled; a single press still jumps
+                if key == Key::Space
+                    && let Some(window) = self.double_tap_fly
+                {
+                    let now = self.clock;
+                    let double_tap = self
+                        .last_jump_press
+                        .is_some_and(|last| now - last <= window);
+                    if double_tap {
+                        self.toggle_fly_mode();
+                        // A third tap starts a new double tap
+                        self.last_jump_press = None;
+                    } else {
+                        self.last_jump_press = Some(now);
                     }
                 }
+
+                // Toggle the build grid with G key
+                if key == Key::G {
+                    self.build_grid = !self.build_grid;
+                }
+
+                // Toggle the chunk mesh statistics with F3 key
+                if key == Key::F3 {
+                    self.chunk_stats = !self.chunk_stats;
+                }
+
+                // Toggle the coordinate axes with F6 key
+                if key == Key::F6 {
+                    self.show_axes = !self.show_axes;
+                }
+
+                // Toggle the re-mesh heat map with F7 key
+                if key == Key::F7 {
+                    self.mesh_heat = !self.mesh_heat;
+                }
+
+                // Use the fill tool with B (set corner), Enter (fill) and Backspace (clear)
+                match key {
+                    Key::B => self.fill_requested = Some(FillAction::SetCorner),
+                    Key::Enter => self.fill_requested = Some(FillAction::Fill),
+                    Key::Backspace => self.fill_requested = Some(FillAction::Clear),
+                    _ => {}
+                }
+
+                // Take a screenshot with F2 key
+                if key == Key::F2 {
+                    self.screenshot_requested = true;
+                }
+
+                // Regenerate the world with a new seed with F9 key
+                if key == Key::F9 {
+                    self.regenerate_requested = true;
+                }
+
+                // Cycle the mirror plane with M key
+                if key == Key::M {
+                    self.mirror_requested = true;
+                }
+
+                // Select a hotbar slot with the number keys
+                let slot = match key {
+                    Key::Num1 => Some(0),
+                    Key::Num2 => Some(1),
+                    Key::Num3 => Some(2),
+                    Key::Num4 => Some(3),
+                    Key::Num5 => Some(4),
+                    Key::Num6 => Some(5),
+                    Key::Num7 => Some(6),
+                    Key::Num8 => Some(7),
+                    Key::Num9 => Some(8),
+                    Key::Num0 => Some(9),
+                    _ => None,
+                };
+                if let Some(slot) = slot {
+                    self.palette_index = slot;
+                }
+
+                self.cycle_palette_with_key(key);
             }
             Action::Release => {
                 self.key_states.insert(key, false);
             }
-            _ => {}
+            // Holding a palette key keeps cycling
+            Action::Repeat => self.cycle_palette_with_key(key),
+        }
+    }
+
+    /// Selects the next or previous block type if the key is one of the palette keys.
+    fn cycle_palette_with_key(&mut self, key: Key) {
+        let (next, previous) = self.palette_keys;
+        if key == next {
+            self.cycle_palette(1);
+        } else if key == previous {
+            self.cycle_palette(-1);
+        }
+    }
+
+    /// Moves the selection through the palette by the given number of entries, wrapping
+    /// around at the ends.
+    fn cycle_palette(&mut self, step: i32) {
+        self.palette_index =
+            (self.palette_index as i32 + step).rem_euclid(PALETTE.len() as i32) as usize;
+    }
+
+    /// Switches between walking and fly mode.
+    fn toggle_fly_mode(&mut self) {
+        self.fly_mode = !self.fly_mode;
+        if self.fly_mode {
+            self.velocity.y = 0.0; // Reset vertical velocity when entering fly mode
         }
     }
 
@@ -78,21 +271,205 @@ impl PlayerInput {
         *self.key_states.get(&key).unwrap_or(&false)
     }
 
-    /// Placeholder method for block placement.
+    /// Advances the clock double taps are timed with.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - The time elapsed since the last frame.
+    pub fn advance_clock(&mut self, delta_time: f32) {
+        self.clock += delta_time;
+    }
+
+    /// Returns the physics and movement state that carries over between frames.
+    pub fn motion_state(&self) -> MotionState {
+        MotionState {
+            velocity: self.velocity,
+            on_ground: self.on_ground,
+            last_jump_time: self.last_jump_time,
+            fly_mode: self.fly_mode,
+            sprinting: self.sprinting,
+            sprint_key_held: self.sprint_key_held,
+        }
+    }
+
+    /// Restores a state returned by `motion_state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to restore.
+    pub fn restore_motion_state(&mut self, state: MotionState) {
+        self.velocity = state.velocity;
+        self.on_ground = state.on_ground;
+        self.last_jump_time = state.last_jump_time;
+        self.fly_mode = state.fly_mode;
+        self.sprinting = state.sprinting;
+        self.sprint_key_held = state.sprint_key_held;
+    }
+
+    /// Updates whether the player is sprinting from the sprint key.
+    ///
+    /// In toggle mode, each press of the key switches sprinting on or off, and sprinting
+    /// ends when no movement key is held. Presses are detected from the held key state, so
+    /// replays toggle sprint the same way. Call this once per frame before moving.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - How the sprint key controls sprinting.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the player is sprinting this frame.
+    pub fn update_sprint(&mut self, mode: SprintMode) -> bool {
+        let held = self.is_key_pressed(Key::LeftControl);
+        let pressed = held && !self.sprint_key_held;
+        self.sprint_key_held = held;
+
+        self.sprinting = match mode {
+            SprintMode::Hold => held,
+            SprintMode::Toggle => {
+                let moving = [Key::W, Key::A, Key::S, Key::D]
+                    .iter()
+                    .any(|&key| self.is_key_pressed(key));
+                (self.sprinting != pressed) && moving
+            }
+        };
+        self.sprinting
+    }
+
+    /// Sets whether a key is held, as if it had been pressed or released.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to set.
+    /// * `pressed` - Whether the key is held.
+    pub fn set_key_pressed(&mut self, key: Key, pressed: bool) {
+        self.key_states.insert(key, pressed);
+    }
+
+    /// Forgets all held keys and buttons and pending requests.
+    ///
+    /// Used when the cursor is released, since the matching release events won't be
+    /// delivered while input is ignored.
+    pub fn release_all(&mut self) {
+        self.key_states.clear();
+        self.breaking = false;
+        self.place_requested = false;
+        self.fill_requested = None;
+        self.mirror_requested = false;
+    }
+
+    /// Handles mouse button press and release events.
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The mouse button that was pressed or released.
+    /// * `action` - The action (press or release) associated with the button.
+    pub fn mouse_button_callback(&mut self, button: MouseButton, action: Action) {
+        match (button, action) {
+            (glfw::MouseButtonLeft, Action::Press) => self.breaking = true,
+            (glfw::MouseButtonLeft, Action::Release) => self.breaking = false,
+            (glfw::MouseButtonRight, Action::Press) => self.place_requested = true,
+            _ => {}
+        }
+    }
+
+    /// Handles mouse wheel events.
+    ///
+    /// Scrolling while holding Left Alt changes the reach. Otherwise the wheel cycles
+    /// through the palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `y_offset` - The vertical scroll offset; positive values scroll up.
+    /// * `config` - A reference to the game configuration.
+    pub fn scroll_callback(&mut self, y_offset: f32, config: &Config) {
+        if y_offset == 0.0 {
+            return;
+        }
+
+        if self.is_key_pressed(Key::LeftAlt) {
+            self.reach_in_blocks = (self.reach_in_blocks + y_offset * REACH_STEP).clamp(
+                config.controls.min_reach_in_blocks,
+                config.controls.max_reach_in_blocks,
+            );
+        } else {
+            // Scrolling up selects the previous block type
+            self.cycle_palette(if y_offset > 0.0 { -1 } else { 1 });
+        }
+    }
+
+    /// Returns the block id of the selected palette entry.
+    pub fn selected_block(&self) -> u32 {
+        PALETTE[self.palette_index]
+    }
+
+    /// Consumes a pending block placement request.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the player clicked to place a block since the last call.
+    pub fn place_block(&mut self) -> bool {
+        std::mem::take(&mut self.place_requested)
+    }
+
+    /// Returns whether the player is holding the button to break blocks.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// Consumes a pending fill tool action.
+    ///
+    /// # Returns
+    ///
+    /// The fill tool action the player requested since the last call, if any.
+    pub fn fill_action(&mut self) -> Option<FillAction> {
+        self.fill_requested.take()
+    }
+
+    /// Consumes a pending screenshot request.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the player pressed the screenshot key since the last call.
+    pub fn take_screenshot(&mut self) -> bool {
+        std::mem::take(&mut self.screenshot_requested)
+    }
+
+    /// Consumes a pending request to cycle the mirror plane.
     ///
     /// # Returns
     ///
-    /// `false` as block placement is not yet implemented.
-    pub fn _place_block(&self) -> bool {
-        false
+    /// `true` if the player pressed the mirror key since the last call.
+    pub fn mirror_action(&mut self) -> bool {
+        std::mem::take(&mut self.mirror_requested)
     }
 
-    /// Placeholder method for block breaking.
+    /// Consumes a pending request to regenerate the world with a new seed.
     ///
     /// # Returns
     ///
-    /// `false` as block breaking is not yet implemented.
-    pub fn _break_block(&self) -> bool {
-        false
+    /// `true` if the player pressed the regenerate key since the last call.
+    pub fn regenerate_world(&mut self) -> bool {
+        std::mem::take(&mut self.regenerate_requested)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alt_scroll_changes_the_reach_without_the_build_grid() {
+        let config = Config::default();
+        let mut player_input = PlayerInput::new(&config);
+        let reach = player_input.reach_in_blocks;
+
+        player_input.scroll_callback(1.0, &config);
+        assert_eq!(player_input.reach_in_blocks, reach);
+
+        player_input.set_key_pressed(Key::LeftAlt, true);
+        player_input.scroll_callback(1.0, &config);
+        assert_eq!(player_input.reach_in_blocks, reach + REACH_STEP);
+        assert!(!player_input.build_grid);
     }
 }
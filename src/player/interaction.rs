@@ -0,0 +1,353 @@
+extern crate gl;
+
+use crate::config::Config;
+use crate::player::camera::Camera;
+use crate::player::input::PlayerInput;
+use crate::player::movement::PLAYER_RADIUS;
+use crate::rendering::chunk_mesh::ChunkMesh;
+use crate::rendering::highlight::BlockHighlight;
+use crate::rendering::particles::ParticleSystem;
+use crate::rendering::shader::Shader;
+use crate::rendering::text::TextRenderer;
+use crate::world::block::{Block, BlockBox};
+use crate::world::chunk::{CHUNK_HEIGHT, chunk_coords};
+use crate::world::generation::Terrain;
+use crate::world::meshing::build_block_vertices;
+use crate::world::raycast::{BlockHit, raycast};
+use crate::world::world::World;
+use cgmath::{InnerSpace, Matrix4, Vector3};
+
+/// The lowest opacity of the placement ghost, so it never disappears completely.
+const MIN_GHOST_OPACITY: f32 = 0.05;
+
+/// How much the placement ghost is shrunk toward its center, so its faces never coincide
+/// with the highlight outline or the faces of neighboring blocks.
+const GHOST_INSET: f32 = 0.02;
+
+/// How far apart the view ray is sampled when looking for the terrain surface, in blocks.
+const SURFACE_SAMPLE_STEP: f32 = 0.25;
+
+/// How far above or below the top of a column the view ray may pass for a block to be
+/// placed on it without a targeted block, in blocks.
+const SURFACE_SNAP_DISTANCE: f32 = 1.0;
+
+/// The size of the break progress bar, in screen pixels.
+const PROGRESS_BAR_SIZE: (f32, f32) = (64.0, 6.0);
+
+/// The background and fill colors of the break progress bar.
+const PROGRESS_BAR_COLORS: ([f32; 4], [f32; 4]) = ([0.0, 0.0, 0.0, 0.6], [1.0, 1.0, 1.0, 0.9]);
+
+/// Represents the translucent preview of the block that would be placed.
+#[derive(Clone, Copy, Debug)]
+pub struct PlacementGhost {
+    /// The world block coordinates the block would be placed at.
+    pub position: [i32; 3],
+    /// The oriented id of the block that would be placed.
+    pub block_id: u32,
+}
+
+/// Handles breaking and placing blocks at the block the player is looking at.
+pub struct BlockInteraction {
+    /// The targeted block, if it is within reach.
+    pub target: Option<BlockHit>,
+    /// The block looked at past the reach but within the highlight distance.
+    out_of_reach: Option<BlockHit>,
+    pub ghost: Option<PlacementGhost>,
+    /// How far the targeted block has been broken, from `0` to `1`.
+    pub break_progress: f32,
+    /// The block that is currently being broken.
+    breaking: Option<[i32; 3]>,
+    /// The mesh of the placement ghost, with the block id and opacity it was built for.
+    ghost_mesh: Option<(u32, f32, ChunkMesh)>,
+}
+
+impl BlockInteraction {
+    /// Creates a new `BlockInteraction` with no target.
+    ///
+    /// # Returns
+    ///
+    /// A new `BlockInteraction` instance.
+    pub fn new() -> Self {
+        BlockInteraction {
+            target: None,
+            out_of_reach: None,
+            ghost: None,
+            break_progress: 0.0,
+            breaking: None,
+            ghost_mesh: None,
+        }
+    }
+
+    /// Updates the targeted block, advances breaking, and applies pending place requests.
+    ///
+    /// Blocks break after the button is held on them for their hardness; looking at another
+    /// block or releasing the button resets the progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - A reference to the player's camera.
+    /// * `player_input` - A mutable reference to the `PlayerInput` instance.
+    /// * `world` - A mutable reference to the game world.
+    /// * `particles` - The particle system to emit break particles from.
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(
+        &mut self,
+        camera: &Camera,
+        player_input: &mut PlayerInput,
+        world: &mut World,
+        particles: &mut ParticleSystem,
+        delta_time: f32,
+        config: &Config,
+    ) {
+        // Skip interaction if cursor isn't locked (in menus)
+        if !player_input.cursor_locked {
+            self.target = None;
+            self.out_of_reach = None;
+            self.ghost = None;
+            self.reset_breaking();
+            return;
+        }
+
+        // Look as far as the highlight reaches, but only edit blocks within reach
+        let reach = config.reach_distance(player_input.reach_in_blocks);
+        let highlight_distance = config
+            .reach_distance(config.controls.highlight_distance_in_blocks)
+            .max(reach);
+        let hit = raycast(
+            world,
+            camera.position,
+            camera.front,
+            highlight_distance,
+            config.controls.target_through_transparent,
+        );
+        (self.target, self.out_of_reach) = match hit {
+            Some(hit) if hit.distance <= reach => (Some(hit), None),
+            hit => (None, hit),
+        };
+
+        // Without a targeted block, optionally place on the terrain surface the ray passes
+        let placement_target = match self.target {
+            None if config.controls.place_on_surface => {
+                Self::surface_target(camera, world, reach, config)
+            }
+            target => target,
+        };
+
+        // Adventure and spectator players can look at blocks, but not change them
+        let can_edit = config.controls.game_mode.can_edit();
+        self.ghost = placement_target.filter(|_| can_edit).and_then(|hit| {
+            Self::placement_ghost(hit, player_input.selected_block(), camera, world, config)
+        });
+
+        match self.target {
+            Some(hit) if can_edit && player_input.is_breaking() => {
+                if self.breaking != Some(hit.position) {
+                    self.breaking = Some(hit.position);
+                    self.break_progress = 0.0;
+                }
+
+                let [x, y, z] = hit.position;
+                let block = Block::new(world.get_block(x, y, z));
+                let hardness = block.hardness();
+                self.break_progress = if hardness > 0.0 {
+                    self.break_progress + delta_time / hardness
+                } else {
+                    1.0
+                };
+
+                if self.break_progress >= 1.0 {
+                    if world.edit_block(x, y, z, 0) {
+                        particles.burst(hit.position, block.get_color(), world.block_size, config);
+                    }
+                    self.reset_breaking();
+                }
+            }
+            _ => self.reset_breaking(),
+        }
+
+        // Always consume the click so it doesn't apply to a later target
+        let place_requested = player_input.place_block();
+
+        // Several clicks in one frame collapse into a single request, and the cell is checked
+        // again right before placing since breaking above may have edited the world
+        if can_edit
+            && place_requested
+            && let Some(ghost) = self.ghost.take()
+        {
+            let feet = camera.feet_position(config);
+            let height = config.physics.player_height;
+            world.place_block(ghost.position, ghost.block_id, &feet, PLAYER_RADIUS, height);
+        }
+
+        // Rebuild the ghost mesh when the previewed block (or its orientation) or the
+        // opacity changes
+        if let Some(ghost) = self.ghost {
+            let opacity = config.ui.ghost_opacity.clamp(MIN_GHOST_OPACITY, 1.0);
+            let up_to_date = matches!(
+                &self.ghost_mesh,
+                Some((id, alpha, _)) if *id == ghost.block_id && *alpha == opacity
+            );
+            if !up_to_date {
+                let vertices = build_block_vertices(ghost.block_id, opacity);
+                self.ghost_mesh = Some((ghost.block_id, opacity, ChunkMesh::new(&vertices)));
+            }
+        }
+    }
+
+    /// Finds the terrain surface near a view ray that missed every block, such as at the edge
+    /// of a cliff or over a hole down to the bottom of the world.
+    ///
+    /// The ray is sampled within reach, and the first sample within `SURFACE_SNAP_DISTANCE`
+    /// of the top of its column's highest solid block is used. The result is a hit on the up
+    /// face of that block, so a block would be placed on top of the column.
+    fn surface_target(
+        camera: &Camera,
+        world: &World,
+        reach: f32,
+        config: &Config,
+    ) -> Option<BlockHit> {
+        let terrain = Terrain::new(world.seed, &config.world);
+        let direction = camera.front.normalize();
+        let step = SURFACE_SAMPLE_STEP * world.block_size;
+        let samples = (reach / step).floor() as usize;
+
+        (1..=samples).find_map(|sample| {
+            let distance = sample as f32 * step;
+            let point = world.to_block_space(camera.position + direction * distance);
+            let (x, z) = (point.x.floor() as i32, point.z.floor() as i32);
+            if !world.chunks.contains_key(&chunk_coords(x, z)) {
+                return None;
+            }
+
+            let surface = world.surface_height(x, z, terrain);
+            let near = (point.y - (surface + 1) as f32).abs() <= SURFACE_SNAP_DISTANCE;
+            let below_top = surface + 1 < CHUNK_HEIGHT as i32;
+            (near && below_top).then_some(BlockHit {
+                position: [x, surface, z],
+                normal: [0, 1, 0],
+                shape: BlockBox::FULL,
+                distance,
+            })
+        })
+    }
+
+    /// Stops breaking the current block and clears its progress.
+    fn reset_breaking(&mut self) {
+        self.breaking = None;
+        self.break_progress = 0.0;
+    }
+
+    /// Queues the break progress bar above the crosshair while a block is being broken.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text renderer to queue the bar on.
+    /// * `width` - The framebuffer width, in pixels.
+    /// * `height` - The framebuffer height, in pixels.
+    pub fn draw_break_progress(&self, text: &mut TextRenderer, width: f32, height: f32) {
+        if self.break_progress <= 0.0 {
+            return;
+        }
+
+        let (bar_width, bar_height) = PROGRESS_BAR_SIZE;
+        let (background, fill) = PROGRESS_BAR_COLORS;
+        let x = (width - bar_width) / 2.0;
+        let y = height / 2.0 - 24.0 - bar_height;
+        text.queue_rect(x, y, bar_width, bar_height, background);
+        text.queue_rect(
+            x,
+            y,
+            bar_width * self.break_progress.min(1.0),
+            bar_height,
+            fill,
+        );
+    }
+
+    /// Returns the ghost for placing a block against the hit face, if placement is possible.
+    ///
+    /// Directional blocks are oriented by the hit face and the direction the player faces.
+    fn placement_ghost(
+        hit: BlockHit,
+        selected_block: u32,
+        camera: &Camera,
+        world: &World,
+        config: &Config,
+    ) -> Option<PlacementGhost> {
+        // The ray started inside a block, so there is no face to place against
+        let face = hit.face()?;
+
+        // Never preview a block in the highlighted cell itself
+        let position = hit.adjacent_position();
+        if position == hit.position {
+            return None;
+        }
+
+        let [x, y, z] = position;
+        if world.get_block(x, y, z) != 0 {
+            return None;
+        }
+
+        // Don't place blocks inside the player
+        let feet = camera.feet_position(config);
+        let height = config.physics.player_height;
+        if world.check_block_collision(&feet, position, &BlockBox::FULL, PLAYER_RADIUS, height) {
+            return None;
+        }
+
+        Some(PlacementGhost {
+            position,
+            block_id: Block::new(selected_block).oriented(face.normal(), camera.front.into()),
+        })
+    }
+
+    /// Draws the placement ghost, and queues the highlight around the part of the targeted
+    /// (or out of reach) block that was hit.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader to use for rendering the ghost mesh.
+    /// * `highlight` - The block highlight to queue the outline on.
+    /// * `world` - A reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    pub fn draw(
+        &self,
+        chunk_shader: &Shader,
+        highlight: &mut BlockHighlight,
+        world: &World,
+        config: &Config,
+    ) {
+        if let Some(hit) = self.target {
+            highlight.block(hit.position, &hit.shape, config.ui.highlight_color);
+        } else if let Some(hit) = self.out_of_reach {
+            let color = config.ui.highlight_out_of_reach_color;
+            highlight.block(hit.position, &hit.shape, color);
+        }
+
+        let (Some(ghost), Some((_, _, mesh))) = (self.ghost, &self.ghost_mesh) else {
+            return;
+        };
+
+        // Shrink the ghost around the center of its cell
+        let [x, y, z] = ghost.position;
+        let model = world.block_model(Vector3::new(
+            x as f32 + GHOST_INSET,
+            y as f32 + GHOST_INSET,
+            z as f32 + GHOST_INSET,
+        )) * Matrix4::from_scale(1.0 - 2.0 * GHOST_INSET);
+
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("model", &model);
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+
+            mesh.draw();
+
+            gl::DepthMask(gl::TRUE);
+            gl::Disable(gl::BLEND);
+        }
+    }
+}
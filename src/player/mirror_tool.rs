@@ -0,0 +1,83 @@
+use crate::player::input::PlayerInput;
+use crate::rendering::gizmos::Gizmos;
+use crate::world::chunk::CHUNK_HEIGHT;
+use crate::world::mirror::{MirrorAxis, MirrorPlane};
+use crate::world::world::World;
+use cgmath::Point3;
+
+/// The color of the mirror plane grid.
+const PLANE_COLOR: [f32; 4] = [0.4, 0.8, 1.0, 0.6];
+
+/// How far the mirror plane grid reaches from the player, in blocks.
+const PLANE_EXTENT: i32 = 12;
+
+/// Cycles the mirror plane when requested: off, then across X, then across Z, then off.
+///
+/// The plane runs through the middle of the anchor block's column.
+///
+/// # Arguments
+///
+/// * `anchor` - The block the plane is placed at, usually the targeted block.
+/// * `player_input` - A mutable reference to the `PlayerInput` instance.
+/// * `world` - A mutable reference to the game world.
+pub fn update_mirror(anchor: [i32; 3], player_input: &mut PlayerInput, world: &mut World) {
+    if !player_input.mirror_action() {
+        return;
+    }
+
+    world.mirror = match world.mirror.map(|plane| plane.axis) {
+        None => Some(MirrorPlane {
+            axis: MirrorAxis::X,
+            coordinate: anchor[0],
+        }),
+        Some(MirrorAxis::X) => Some(MirrorPlane {
+            axis: MirrorAxis::Z,
+            coordinate: anchor[2],
+        }),
+        Some(MirrorAxis::Z) => None,
+    };
+}
+
+/// Queues a grid showing the mirror plane around the player, if the mirror tool is on.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `player` - The player's position, in world units.
+/// * `gizmos` - The gizmos to queue the grid on.
+pub fn draw_mirror_plane(world: &World, player: Point3<f32>, gizmos: &mut Gizmos) {
+    let Some(plane) = world.mirror else {
+        return;
+    };
+
+    let center = world.to_block_space(player);
+    let (across, along) = match plane.axis {
+        MirrorAxis::X => (0, 2),
+        MirrorAxis::Z => (2, 0),
+    };
+    let center_along = [center.x, center.y, center.z][along].floor() as i32;
+    let min_y = (center.y.floor() as i32 - PLANE_EXTENT).max(0);
+    let max_y = (center.y.floor() as i32 + PLANE_EXTENT).min(CHUNK_HEIGHT as i32);
+
+    // Maps a point on the plane, given by its horizontal and vertical block offsets, into
+    // world units
+    let point = |horizontal: i32, y: i32| {
+        let mut block = [0.0; 3];
+        block[across] = plane.coordinate as f32 + 0.5;
+        block[along] = horizontal as f32;
+        block[1] = y as f32;
+        Point3::new(block[0], block[1], block[2]) * world.block_size
+    };
+
+    let (min_along, max_along) = (center_along - PLANE_EXTENT, center_along + PLANE_EXTENT);
+    for horizontal in min_along..=max_along {
+        gizmos.line(
+            point(horizontal, min_y),
+            point(horizontal, max_y),
+            PLANE_COLOR,
+        );
+    }
+    for y in min_y..=max_y {
+        gizmos.line(point(min_along, y), point(max_along, y), PLANE_COLOR);
+    }
+}
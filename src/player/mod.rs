@@ -1,3 +1,9 @@
+pub mod build_helper;
 pub mod camera;
+pub mod fill_tool;
 pub mod input;
+pub mod interaction;
+pub mod mirror_tool;
 pub mod movement; // New module
+pub mod replay;
+pub mod view_bob;
@@ -1,11 +1,69 @@
-use crate::config::Config;
+use crate::config::{Config, GameMode};
 use crate::player::camera::Camera;
 use crate::player::input::PlayerInput;
+use crate::world::block::Block;
+use crate::world::border::WorldBorder;
 use crate::world::world::World;
 use cgmath::InnerSpace;
-use cgmath::Point3;
+use cgmath::{Point3, Vector3};
 use glfw::{Key, Window};
 
+/// How far ahead of the player a step is looked for, in blocks.
+const STEP_PROBE_DISTANCE: f32 = 0.2;
+
+/// The player collision radius, shared by movement and block placement.
+pub const PLAYER_RADIUS: f32 = 0.3;
+
+/// Returns whether the player is walking into a full block step exactly one block high,
+/// with room to stand on top of it.
+///
+/// Partial blocks such as slabs don't count, since the player steps onto those without
+/// jumping.
+///
+/// # Arguments
+///
+/// * `feet_position` - The position of the player's feet, in world units.
+/// * `movement` - The horizontal movement of the player this frame, in world units.
+/// * `radius` - The collision radius of the player.
+/// * `height` - The height of the player.
+/// * `world` - A reference to the game world.
+fn is_walking_into_step(
+    feet_position: Point3<f32>,
+    movement: Vector3<f32>,
+    radius: f32,
+    height: f32,
+    world: &World,
+) -> bool {
+    if movement.magnitude() < 0.00001 {
+        return false;
+    }
+
+    // Look just past the player in the direction they walk, slightly above the ground so
+    // the ground itself doesn't count as a step
+    let size = world.block_size;
+    let direction = movement.normalize();
+    let probe = feet_position + direction * STEP_PROBE_DISTANCE * size;
+    let blocked = Point3::new(probe.x, probe.y + 0.05 * size, probe.z);
+    if !world.check_collision(&blocked, radius, 0.1 * size) {
+        return false;
+    }
+
+    // The block just past the front of the player must be the full block being walked into
+    let front = world.to_block_space(blocked + direction * radius);
+    let step = Block::new(world.get_block(
+        front.x.floor() as i32,
+        front.y.floor() as i32,
+        front.z.floor() as i32,
+    ));
+    if !step.is_solid() || !step.is_full_cube() {
+        return false;
+    }
+
+    // Walls two or more blocks high, or a low ceiling above the step, leave no room to stand
+    let on_step = Point3::new(probe.x, feet_position.y + 1.05 * size, probe.z);
+    !world.check_collision(&on_step, radius, height - 0.1 * size)
+}
+
 /// Processes player input and updates the camera position.
 ///
 /// # Arguments
@@ -25,14 +83,25 @@ pub fn process_input(
 ) {
     // Update timers
     player_input.last_jump_time += delta_time;
+    player_input.advance_clock(delta_time);
 
-    // Set movement speed
-    let base_speed = if player_input.is_key_pressed(Key::LeftControl) {
-        15.0 // Sprint speed
-    } else if player_input.fly_mode {
-        8.0 // Fly mode speed
+    // Set movement speed; flying has its own horizontal and vertical speeds, and the walk
+    // speeds apply again as soon as the player lands
+    let sprinting = player_input.update_sprint(config.controls.sprint_mode);
+    let (base_speed, vertical_speed) = if player_input.fly_mode {
+        let boost = if sprinting {
+            config.controls.fly_sprint_multiplier
+        } else {
+            1.0
+        };
+        (
+            config.controls.fly_speed * boost,
+            config.controls.fly_vertical_speed * boost,
+        )
+    } else if sprinting {
+        (15.0, 0.0) // Sprint speed
     } else {
-        5.0 // Normal walking speed
+        (5.0, 0.0) // Normal walking speed
     };
 
     // Calculate frame-dependent speed
@@ -40,7 +109,6 @@ pub fn process_input(
     player_input.movement_speed = base_speed;
 
     // Player collision properties
-    let player_radius = 0.3; // Reasonable radius for player
     let player_height = config.physics.player_height;
 
     // Store current position before movement
@@ -50,11 +118,7 @@ pub fn process_input(
     // Ground detection for jumping
     if !player_input.fly_mode {
         // Position is now the camera position (eyes), so we check at feet level
-        let feet_position = Point3::new(
-            camera.position.x,
-            camera.position.y - config.physics.player_height,
-            camera.position.z,
-        );
+        let feet_position = camera.feet_position(config);
 
         // Check if there's a block below feet
         let ground_check_pos = Point3::new(
@@ -64,7 +128,7 @@ pub fn process_input(
         );
 
         let was_on_ground = player_input.on_ground;
-        player_input.on_ground = world.check_collision(&ground_check_pos, player_radius, 0.1);
+        player_input.on_ground = world.check_collision(&ground_check_pos, PLAYER_RADIUS, 0.1);
 
         // If we just landed, reset vertical velocity
         if !was_on_ground && player_input.on_ground {
@@ -108,14 +172,22 @@ pub fn process_input(
     if player_input.fly_mode {
         // Flying controls - direct up/down movement
         if player_input.is_key_pressed(Key::Space) {
-            target_pos.y += speed;
+            target_pos.y += vertical_speed * delta_time;
         }
         if player_input.is_key_pressed(Key::LeftShift) {
-            target_pos.y -= speed;
+            target_pos.y -= vertical_speed * delta_time;
         }
     } else {
         // Walking mode with physics and jumping
-        if player_input.is_key_pressed(Key::Space)
+        let feet_position = camera.feet_position(config);
+        let walking = Vector3::new(
+            target_pos.x - current_pos.x,
+            0.0,
+            target_pos.z - current_pos.z,
+        );
+        let auto_jump = config.physics.auto_jump
+            && is_walking_into_step(feet_position, walking, PLAYER_RADIUS, player_height, world);
+        if (player_input.is_key_pressed(Key::Space) || auto_jump)
             && player_input.on_ground
             && player_input.last_jump_time > player_input.jump_cooldown
         {
@@ -136,21 +208,42 @@ pub fn process_input(
         target_pos.y += player_input.velocity.y;
     }
 
-    // Resolve collisions with world
-    camera.position =
-        world.resolve_collision(current_pos, target_pos, player_radius, player_height);
+    // Keep the player inside the world border
+    if let Some(border) = WorldBorder::new(&config.world) {
+        target_pos = border.push_back(
+            current_pos,
+            target_pos,
+            PLAYER_RADIUS,
+            world.block_size,
+            delta_time,
+        );
+    }
+
+    // Resolve collisions with world; spectators pass through blocks
+    if !config.controls.game_mode.has_collision() {
+        camera.position = target_pos;
+        return;
+    }
+    camera.position = world.resolve_collision(
+        current_pos,
+        target_pos,
+        PLAYER_RADIUS,
+        player_height,
+        config.physics.eye_height,
+    );
 
     // Ground detection for jumping
     if !player_input.fly_mode {
         // Check if there's a block below us
+        let feet_position = camera.feet_position(config);
         let ground_check_pos = Point3::new(
-            camera.position.x,
-            camera.position.y - 0.05, // Check slightly below feet
-            camera.position.z,
+            feet_position.x,
+            feet_position.y - 0.05, // Check slightly below feet
+            feet_position.z,
         );
 
         let was_on_ground = player_input.on_ground;
-        player_input.on_ground = world.check_collision(&ground_check_pos, player_radius, 0.1);
+        player_input.on_ground = world.check_collision(&ground_check_pos, PLAYER_RADIUS, 0.1);
 
         // If we just landed, reset vertical velocity
         if !was_on_ground && player_input.on_ground {
@@ -178,9 +271,73 @@ pub fn handle_movement_input(
     world: &World,
 ) {
     // Skip input processing if cursor isn't locked (in menus)
-    if !config.controls.cursor_locked {
+    if !player_input.cursor_locked {
         return;
     }
 
+    // The game mode decides whether the player may fly, or always flies
+    match config.controls.game_mode {
+        GameMode::Spectator => player_input.fly_mode = true,
+        mode if !mode.can_fly() => player_input.fly_mode = false,
+        _ => {}
+    }
+
     process_input(player_input, window, camera, delta_time, config, world);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a world with a stone floor at y = 0 and the given block at (8, 1, 5).
+    fn world_with_step(block_id: u32) -> World {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        for x in 0..16 {
+            for z in 0..16 {
+                world.set_block(x, 0, z, 3);
+            }
+        }
+        world.set_block(8, 1, 5, block_id);
+        world
+    }
+
+    #[test]
+    fn walking_into_a_single_step_jumps() {
+        let feet = Point3::new(7.6, 1.0, 5.5);
+        let walking = Vector3::new(0.1, 0.0, 0.0);
+
+        assert!(is_walking_into_step(
+            feet,
+            walking,
+            PLAYER_RADIUS,
+            1.8,
+            &world_with_step(3)
+        ));
+        assert!(!is_walking_into_step(
+            feet,
+            -walking,
+            PLAYER_RADIUS,
+            1.8,
+            &world_with_step(3)
+        ));
+        // Slabs are stepped onto instead
+        assert!(!is_walking_into_step(
+            feet,
+            walking,
+            PLAYER_RADIUS,
+            1.8,
+            &world_with_step(9)
+        ));
+
+        // A second block on top makes a wall
+        let mut wall = world_with_step(3);
+        wall.set_block(8, 2, 5, 3);
+        assert!(!is_walking_into_step(
+            feet,
+            walking,
+            PLAYER_RADIUS,
+            1.8,
+            &wall
+        ));
+    }
+}
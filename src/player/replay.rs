@@ -0,0 +1,310 @@
+use crate::config::{Config, ReplayMode};
+use crate::player::camera::Camera;
+use crate::player::input::{MotionState, PlayerInput};
+use cgmath::{Point3, Vector3};
+use glfw::Key;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+/// The keys that drive player movement, with the names they are stored under.
+const RECORDED_KEYS: [(Key, &str); 7] = [
+    (Key::W, "W"),
+    (Key::A, "A"),
+    (Key::S, "S"),
+    (Key::D, "D"),
+    (Key::Space, "Space"),
+    (Key::LeftShift, "LeftShift"),
+    (Key::LeftControl, "LeftControl"),
+];
+
+/// The movement input of one recorded frame.
+struct ReplayFrame {
+    delta_time: f32,
+    yaw: f32,
+    pitch: f32,
+    fly_mode: bool,
+    keys: Vec<Key>,
+}
+
+/// Records the player's movement input to a file, or plays a recording back.
+///
+/// A recording is a text file starting with a `start x y z` line holding the camera
+/// position and a `state vx vy vz on_ground last_jump_time fly sprinting sprint_key_held`
+/// line holding the player's motion state, followed by one
+/// `time delta_time yaw pitch fly keys` line per frame. Flags are `0` or `1`, and `keys`
+/// lists the held movement keys joined by `+` (or `-` for none). Playback restores the
+/// start position and state and feeds each frame's input and frame time to the game
+/// instead of the live values, so physics glitches can be reproduced. Chunks still load in
+/// real time, so a replay should start in terrain that is already loaded.
+pub struct Replay {
+    mode: ReplayMode,
+    writer: Option<BufWriter<File>>,
+    start: Option<Point3<f32>>,
+    state: Option<MotionState>,
+    frames: VecDeque<ReplayFrame>,
+    time: f32,
+}
+
+impl Replay {
+    /// Creates a new `Replay` in the configured mode.
+    ///
+    /// Recording creates (or truncates) the replay file, and playback reads it up front. If
+    /// the file can't be opened or parsed, the error is logged and replays stay off.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the game configuration.
+    ///
+    /// # Returns
+    ///
+    /// A new `Replay` instance.
+    pub fn new(config: &Config) -> Self {
+        let mut replay = Replay {
+            mode: config.controls.replay_mode,
+            writer: None,
+            start: None,
+            state: None,
+            frames: VecDeque::new(),
+            time: 0.0,
+        };
+        let path = &config.controls.replay_file;
+
+        match replay.mode {
+            ReplayMode::Off => {}
+            ReplayMode::Record => match File::create(path) {
+                Ok(file) => replay.writer = Some(BufWriter::new(file)),
+                Err(e) => {
+                    eprintln!("Error creating replay file {}: {}", path, e);
+                    replay.mode = ReplayMode::Off;
+                }
+            },
+            ReplayMode::Playback => match fs::read_to_string(path) {
+                Ok(content) => {
+                    if let Err(line) = replay.parse(&content) {
+                        eprintln!("Invalid replay file {} at line {}", path, line);
+                        replay.mode = ReplayMode::Off;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error reading replay file {}: {}", path, e);
+                    replay.mode = ReplayMode::Off;
+                }
+            },
+        }
+
+        replay
+    }
+
+    /// Records or replaces the movement input of the current frame.
+    ///
+    /// Call this after processing window events and before moving the player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_input` - A mutable reference to the `PlayerInput` instance.
+    /// * `camera` - A mutable reference to the player's camera.
+    /// * `delta_time` - The measured time elapsed since the last frame.
+    ///
+    /// # Returns
+    ///
+    /// The frame time to simulate this frame with: the recorded one during playback, or
+    /// `delta_time` otherwise.
+    pub fn process(
+        &mut self,
+        player_input: &mut PlayerInput,
+        camera: &mut Camera,
+        delta_time: f32,
+    ) -> f32 {
+        match self.mode {
+            ReplayMode::Off => delta_time,
+            ReplayMode::Record => {
+                self.record(player_input, camera, delta_time);
+                delta_time
+            }
+            ReplayMode::Playback => self.play(player_input, camera).unwrap_or(delta_time),
+        }
+    }
+
+    /// Writes the current frame's input to the replay file.
+    fn record(&mut self, player_input: &PlayerInput, camera: &Camera, delta_time: f32) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+
+        let mut lines = String::new();
+        if self.start.is_none() {
+            let position = camera.position;
+            let state = player_input.motion_state();
+            lines += &format!("start {} {} {}\n", position.x, position.y, position.z);
+            lines += &format!(
+                "state {} {} {} {} {} {} {} {}\n",
+                state.velocity.x,
+                state.velocity.y,
+                state.velocity.z,
+                state.on_ground as u8,
+                state.last_jump_time,
+                state.fly_mode as u8,
+                state.sprinting as u8,
+                state.sprint_key_held as u8
+            );
+            self.start = Some(position);
+        }
+        self.time += delta_time;
+
+        let keys: Vec<&str> = RECORDED_KEYS
+            .iter()
+            .filter(|(key, _)| player_input.is_key_pressed(*key))
+            .map(|(_, name)| *name)
+            .collect();
+        lines += &format!(
+            "{} {} {} {} {} {}\n",
+            self.time,
+            delta_time,
+            camera.yaw,
+            camera.pitch,
+            player_input.fly_mode as u8,
+            if keys.is_empty() {
+                String::from("-")
+            } else {
+                keys.join("+")
+            }
+        );
+
+        if let Err(e) = writer.write_all(lines.as_bytes()) {
+            eprintln!("Error writing replay file: {}; recording stopped", e);
+            self.writer = None;
+            self.mode = ReplayMode::Off;
+        }
+    }
+
+    /// Applies the next recorded frame, or ends playback when the recording is over.
+    fn play(&mut self, player_input: &mut PlayerInput, camera: &mut Camera) -> Option<f32> {
+        if let Some(start) = self.start.take() {
+            camera.position = start;
+            // Recordings without a state line start at rest
+            match self.state.take() {
+                Some(state) => player_input.restore_motion_state(state),
+                None => player_input.velocity = Vector3::new(0.0, 0.0, 0.0),
+            }
+        }
+
+        let Some(frame) = self.frames.pop_front() else {
+            // Hand control back to the player
+            println!("Replay finished");
+            player_input.release_all();
+            self.mode = ReplayMode::Off;
+            return None;
+        };
+
+        camera.yaw = frame.yaw;
+        camera.pitch = frame.pitch;
+        camera.update_camera_vectors();
+        player_input.fly_mode = frame.fly_mode;
+        for (key, _) in RECORDED_KEYS {
+            player_input.set_key_pressed(key, frame.keys.contains(&key));
+        }
+
+        Some(frame.delta_time)
+    }
+
+    /// Reads a recording into the playback queue.
+    ///
+    /// # Returns
+    ///
+    /// The number of the first invalid line, if any.
+    fn parse(&mut self, content: &str) -> Result<(), usize> {
+        for (number, line) in content.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let float = |index: usize| -> Result<f32, usize> {
+                fields
+                    .get(index)
+                    .and_then(|field| field.parse().ok())
+                    .ok_or(number + 1)
+            };
+
+            let flag = |index: usize| -> Result<bool, usize> {
+                match fields.get(index) {
+                    Some(&"0") => Ok(false),
+                    Some(&"1") => Ok(true),
+                    _ => Err(number + 1),
+                }
+            };
+
+            match fields.as_slice() {
+                [] => {}
+                ["start", ..] => {
+                    self.start = Some(Point3::new(float(1)?, float(2)?, float(3)?));
+                }
+                ["state", ..] => {
+                    self.state = Some(MotionState {
+                        velocity: Vector3::new(float(1)?, float(2)?, float(3)?),
+                        on_ground: flag(4)?,
+                        last_jump_time: float(5)?,
+                        fly_mode: flag(6)?,
+                        sprinting: flag(7)?,
+                        sprint_key_held: flag(8)?,
+                    });
+                }
+                [_, _, _, _, fly, keys] => {
+                    let keys = keys
+                        .split('+')
+                        .filter(|name| *name != "-")
+                        .map(|name| {
+                            RECORDED_KEYS
+                                .iter()
+                                .find(|(_, key_name)| *key_name == name)
+                                .map(|(key, _)| *key)
+                                .ok_or(number + 1)
+                        })
+                        .collect::<Result<Vec<Key>, usize>>()?;
+                    self.frames.push_back(ReplayFrame {
+                        delta_time: float(1)?,
+                        yaw: float(2)?,
+                        pitch: float(3)?,
+                        fly_mode: *fly == "1",
+                        keys,
+                    });
+                }
+                _ => return Err(number + 1),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_restores_the_recorded_motion_state() {
+        let config = Config::default();
+        let mut replay = Replay::new(&config);
+        replay
+            .parse("start 1 2 3\nstate 0.5 -4 0 0 0.1 0 1 1\n0.016 0.016 -90 10 0 W+LeftControl\n")
+            .unwrap();
+
+        let mut player_input = PlayerInput::new(&config);
+        player_input.on_ground = true;
+        let mut camera = Camera::new(Point3::new(0.0, 0.0, 0.0));
+        replay.mode = ReplayMode::Playback;
+        let delta_time = replay.process(&mut player_input, &mut camera, 1.0);
+
+        assert_eq!(delta_time, 0.016);
+        assert_eq!(camera.position, Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(
+            player_input.motion_state(),
+            MotionState {
+                velocity: Vector3::new(0.5, -4.0, 0.0),
+                on_ground: false,
+                last_jump_time: 0.1,
+                fly_mode: false,
+                sprinting: true,
+                sprint_key_held: true,
+            }
+        );
+        assert!(player_input.is_key_pressed(Key::LeftControl));
+    }
+}
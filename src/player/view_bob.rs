@@ -0,0 +1,94 @@
+use crate::config::Config;
+use crate::player::camera::Camera;
+use crate::player::input::PlayerInput;
+use cgmath::{InnerSpace, Vector3};
+use std::f32::consts::TAU;
+
+/// The walking speed at which the bob reaches full amplitude, in world units per second.
+const WALK_SPEED: f32 = 5.0;
+
+/// The highest bob intensity, reached while sprinting.
+const MAX_INTENSITY: f32 = 1.5;
+
+/// How quickly the bob fades in and out, per second.
+const SMOOTHING: f32 = 8.0;
+
+/// Offsets the camera while walking to simulate the player's steps.
+///
+/// The bob follows a phase that advances with the distance walked, so sprinting bobs faster,
+/// and its intensity eases toward zero when the player stops or leaves the ground.
+pub struct ViewBob {
+    phase: f32,
+    intensity: f32,
+}
+
+impl ViewBob {
+    /// Creates a new `ViewBob` at rest.
+    ///
+    /// # Returns
+    ///
+    /// A new `ViewBob` instance.
+    pub fn new() -> Self {
+        ViewBob {
+            phase: 0.0,
+            intensity: 0.0,
+        }
+    }
+
+    /// Advances the bob based on how far the player moved this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `movement` - The camera's movement this frame, in world units.
+    /// * `player_input` - A reference to the `PlayerInput` instance.
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(
+        &mut self,
+        movement: Vector3<f32>,
+        player_input: &PlayerInput,
+        delta_time: f32,
+        config: &Config,
+    ) {
+        if delta_time <= 0.0 {
+            return;
+        }
+
+        let horizontal_speed = Vector3::new(movement.x, 0.0, movement.z).magnitude() / delta_time;
+        let walking =
+            config.graphics.view_bobbing && player_input.on_ground && !player_input.fly_mode;
+        let target = if walking {
+            (horizontal_speed / WALK_SPEED).min(MAX_INTENSITY)
+        } else {
+            0.0
+        };
+
+        self.intensity += (target - self.intensity) * (SMOOTHING * delta_time).min(1.0);
+        self.phase = (self.phase
+            + delta_time * config.graphics.bob_frequency * TAU * horizontal_speed / WALK_SPEED)
+            % TAU;
+    }
+
+    /// Returns the current camera offset, in world units.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - A reference to the player's camera.
+    /// * `config` - A reference to the game configuration.
+    ///
+    /// # Returns
+    ///
+    /// The offset to add to the camera position when building the view matrix.
+    pub fn offset(&self, camera: &Camera, config: &Config) -> Vector3<f32> {
+        let amplitude = config.graphics.bob_amplitude * self.intensity;
+        if amplitude <= 0.0 {
+            return Vector3::new(0.0, 0.0, 0.0);
+        }
+
+        // Two vertical bobs per stride, swaying once from side to side
+        let right = camera.front.cross(camera.up).normalize();
+        let vertical = (self.phase * 2.0).sin() * amplitude;
+        let horizontal = self.phase.cos() * amplitude * 0.5;
+        camera.up * vertical + right * horizontal
+    }
+}
@@ -0,0 +1,91 @@
+extern crate gl;
+use gl::types::*;
+use std::ptr;
+
+/// The number of floats per chunk mesh vertex: position (3) followed by RGBA color (4).
+pub const VERTEX_STRIDE: usize = 7;
+
+/// Represents the GPU mesh of a single chunk, with per-vertex positions and colors.
+pub struct ChunkMesh {
+    vao: GLuint,
+    vbo: GLuint,
+    vertex_count: i32,
+}
+
+impl ChunkMesh {
+    /// Creates a new `ChunkMesh` by uploading the given interleaved vertex data.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - A slice of interleaved vertex data, `VERTEX_STRIDE` floats per vertex.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChunkMesh` instance.
+    pub fn new(vertices: &[f32]) -> Self {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+        let vertex_count = (vertices.len() / VERTEX_STRIDE) as i32;
+        let stride = (VERTEX_STRIDE * size_of::<GLfloat>()) as GLint;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
+
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                size_of_val(vertices) as GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            // Position attribute
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
+
+            // Color attribute
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(
+                1,
+                4,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * size_of::<GLfloat>()) as *const _,
+            );
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+
+        ChunkMesh {
+            vao,
+            vbo,
+            vertex_count,
+        }
+    }
+
+    /// Draws the mesh using OpenGL.
+    pub fn draw(&self) {
+        if self.vertex_count == 0 {
+            return;
+        }
+
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, self.vertex_count);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for ChunkMesh {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
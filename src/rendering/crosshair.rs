@@ -0,0 +1,64 @@
+extern crate gl;
+
+use crate::rendering::mesh::Mesh;
+use crate::rendering::shader::Shader;
+use cgmath::{Matrix4, SquareMatrix};
+
+/// Half the length of a crosshair arm, in normalized device coordinates.
+const ARM_LENGTH: f32 = 0.02;
+
+/// Half the thickness of a crosshair arm, in normalized device coordinates.
+const ARM_THICKNESS: f32 = 0.002;
+
+/// Represents the crosshair drawn at the center of the screen.
+pub struct Crosshair {
+    mesh: Mesh,
+}
+
+impl Crosshair {
+    /// Creates a new `Crosshair`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Crosshair` instance.
+    pub fn new() -> Self {
+        let (l, t) = (ARM_LENGTH, ARM_THICKNESS);
+        let vertices: [f32; 36] = [
+            // Horizontal arm
+            -l, -t, 0.0, l, -t, 0.0, l, t, 0.0, //
+            l, t, 0.0, -l, t, 0.0, -l, -t, 0.0, //
+            // Vertical arm
+            -t, -l, 0.0, t, -l, 0.0, t, l, 0.0, //
+            t, l, 0.0, -t, l, 0.0, -t, -l, 0.0, //
+        ];
+
+        Crosshair {
+            mesh: Mesh::new(&vertices),
+        }
+    }
+
+    /// Draws the crosshair on top of the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `shader` - The shader to use for rendering.
+    /// * `aspect_ratio` - The framebuffer width divided by its height.
+    pub fn draw(&self, shader: &Shader, aspect_ratio: f32) {
+        shader.use_program();
+        shader.set_mat4("projection", &Matrix4::identity());
+        shader.set_mat4("view", &Matrix4::identity());
+        // Keep the arms the same length on screen regardless of aspect ratio
+        shader.set_mat4(
+            "model",
+            &Matrix4::from_nonuniform_scale(1.0 / aspect_ratio, 1.0, 1.0),
+        );
+
+        shader.set_vec4("color", [1.0, 1.0, 1.0, 1.0]);
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            self.mesh.draw();
+            gl::Enable(gl::DEPTH_TEST);
+        }
+    }
+}
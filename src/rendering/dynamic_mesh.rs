@@ -0,0 +1,80 @@
+extern crate gl;
+use crate::rendering::chunk_mesh::VERTEX_STRIDE;
+use gl::types::*;
+use std::ptr;
+
+/// Represents a mesh whose vertices are re-uploaded every time it is drawn.
+///
+/// Uses the same interleaved layout as `ChunkMesh` (position followed by RGBA color), so it
+/// can be drawn with the chunk shader. Used for per-frame geometry such as gizmos and text.
+pub struct DynamicMesh {
+    vao: GLuint,
+    vbo: GLuint,
+}
+
+impl DynamicMesh {
+    /// Creates a new, empty `DynamicMesh`.
+    ///
+    /// # Returns
+    ///
+    /// A new `DynamicMesh` instance.
+    pub fn new() -> Self {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+        let stride = (VERTEX_STRIDE * size_of::<GLfloat>()) as GLint;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
+
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            // Position attribute
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
+
+            // Color attribute
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(
+                1,
+                4,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * size_of::<GLfloat>()) as *const _,
+            );
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+
+        DynamicMesh { vao, vbo }
+    }
+
+    /// Uploads the given vertices and draws them.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Interleaved vertex data, `VERTEX_STRIDE` floats per vertex.
+    /// * `mode` - The primitive type to draw (e.g., `gl::LINES` or `gl::TRIANGLES`).
+    pub fn draw(&self, vertices: &[f32], mode: GLenum) {
+        if vertices.is_empty() {
+            return;
+        }
+
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                size_of_val(vertices) as GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::DrawArrays(mode, 0, (vertices.len() / VERTEX_STRIDE) as GLsizei);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+extern crate gl;
+
+use crate::rendering::dynamic_mesh::DynamicMesh;
+use crate::rendering::shader::Shader;
+use cgmath::{Matrix4, Point3, SquareMatrix};
+
+/// Collects debug lines during a frame and draws them in a single batch.
+pub struct Gizmos {
+    mesh: DynamicMesh,
+    vertices: Vec<f32>,
+}
+
+impl Gizmos {
+    /// Creates a new `Gizmos` instance with no queued lines.
+    ///
+    /// # Returns
+    ///
+    /// A new `Gizmos` instance.
+    pub fn new() -> Self {
+        Gizmos {
+            mesh: DynamicMesh::new(),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Queues a line to be drawn this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the line, in world units.
+    /// * `end` - The end of the line, in world units.
+    /// * `color` - The RGBA color of the line.
+    pub fn line(&mut self, start: Point3<f32>, end: Point3<f32>, color: [f32; 4]) {
+        for point in [start, end] {
+            self.vertices.extend_from_slice(&[
+                point.x, point.y, point.z, color[0], color[1], color[2], color[3],
+            ]);
+        }
+    }
+
+    /// Queues the twelve edges of an axis-aligned box to be drawn this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum corner of the box, in world units.
+    /// * `max` - The maximum corner of the box, in world units.
+    /// * `color` - The RGBA color of the edges.
+    pub fn cuboid(&mut self, min: Point3<f32>, max: Point3<f32>, color: [f32; 4]) {
+        let corner = |x: bool, y: bool, z: bool| {
+            Point3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+
+        for a in [false, true] {
+            for b in [false, true] {
+                self.line(corner(false, a, b), corner(true, a, b), color);
+                self.line(corner(a, false, b), corner(a, true, b), color);
+                self.line(corner(a, b, false), corner(a, b, true), color);
+            }
+        }
+    }
+
+    /// Queues red, green and blue lines along the positive X, Y and Z axes from a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The point the axes start at, in world units.
+    /// * `length` - The length of each axis line, in world units.
+    pub fn axes(&mut self, origin: Point3<f32>, length: f32) {
+        for (axis, color) in [
+            ([1.0, 0.0, 0.0], [1.0, 0.2, 0.2, 1.0]),
+            ([0.0, 1.0, 0.0], [0.2, 1.0, 0.2, 1.0]),
+            ([0.0, 0.0, 1.0], [0.2, 0.4, 1.0, 1.0]),
+        ] {
+            let end = Point3::new(
+                origin.x + axis[0] * length,
+                origin.y + axis[1] * length,
+                origin.z + axis[2] * length,
+            );
+            self.line(origin, end, color);
+        }
+    }
+
+    /// Discards everything queued this frame without drawing it.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Draws all queued lines and clears the queue.
+    ///
+    /// The projection and view matrices of the shader must already be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader to use for rendering, with per-vertex colors.
+    pub fn draw(&mut self, chunk_shader: &Shader) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("model", &Matrix4::identity());
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::LineWidth(1.0);
+
+            self.mesh.draw(&self.vertices, gl::LINES);
+
+            gl::Disable(gl::BLEND);
+        }
+
+        self.vertices.clear();
+    }
+}
@@ -0,0 +1,237 @@
+extern crate gl;
+
+use crate::config::Config;
+use crate::rendering::dynamic_mesh::DynamicMesh;
+use crate::rendering::shader::Shader;
+use crate::world::block::BlockBox;
+use crate::world::world::World;
+use cgmath::Matrix4;
+
+/// How much a highlight outline is grown past the block faces to avoid z-fighting, in blocks.
+const HIGHLIGHT_MARGIN: f32 = 0.004;
+
+/// How thick the edges of the box outline are per pixel of highlight thickness, in blocks.
+/// Used when the driver can't draw lines as wide as the configured thickness.
+const HIGHLIGHT_EDGE_PER_PIXEL: f32 = 0.004;
+
+/// The corners of the twelve triangles of a box, as offsets of 0 (min) or 1 (max) per axis.
+const BOX_TRIANGLES: [[[usize; 3]; 3]; 12] = [
+    [[0, 0, 0], [1, 0, 0], [1, 1, 0]],
+    [[0, 0, 0], [1, 1, 0], [0, 1, 0]],
+    [[0, 0, 1], [1, 0, 1], [1, 1, 1]],
+    [[0, 0, 1], [1, 1, 1], [0, 1, 1]],
+    [[0, 0, 0], [0, 1, 0], [0, 1, 1]],
+    [[0, 0, 0], [0, 1, 1], [0, 0, 1]],
+    [[1, 0, 0], [1, 1, 0], [1, 1, 1]],
+    [[1, 0, 0], [1, 1, 1], [1, 0, 1]],
+    [[0, 0, 0], [1, 0, 0], [1, 0, 1]],
+    [[0, 0, 0], [1, 0, 1], [0, 0, 1]],
+    [[0, 1, 0], [1, 1, 0], [1, 1, 1]],
+    [[0, 1, 0], [1, 1, 1], [0, 1, 1]],
+];
+
+/// An outlined box, in block space.
+struct HighlightBox {
+    min: [f32; 3],
+    max: [f32; 3],
+    color: [f32; 4],
+}
+
+/// Collects highlight outlines during a frame and draws them in a single batch.
+///
+/// Used for both the targeted block and the fill tool selection. Every highlight is an
+/// axis-aligned box, so a region costs the same as a single block no matter its size.
+/// Outlines use the configured `ui.highlight_thickness`: thick outlines are drawn as lines
+/// where the driver supports the width, and otherwise, such as on core profiles limited to
+/// one pixel wide lines, each edge is drawn as a thin box whose thickness scales with the
+/// setting.
+pub struct BlockHighlight {
+    mesh: DynamicMesh,
+    boxes: Vec<HighlightBox>,
+    vertices: Vec<f32>,
+    /// The widest line the driver can draw, in pixels.
+    max_line_width: f32,
+}
+
+impl BlockHighlight {
+    /// Creates a new `BlockHighlight` with no queued outlines.
+    ///
+    /// Must be called after the OpenGL context is created, since it queries the supported
+    /// line widths.
+    ///
+    /// # Returns
+    ///
+    /// A new `BlockHighlight` instance.
+    pub fn new() -> Self {
+        let mut line_width_range = [1.0f32; 2];
+        unsafe {
+            gl::GetFloatv(gl::ALIASED_LINE_WIDTH_RANGE, line_width_range.as_mut_ptr());
+        }
+
+        BlockHighlight {
+            mesh: DynamicMesh::new(),
+            boxes: Vec::new(),
+            vertices: Vec::new(),
+            max_line_width: line_width_range[1],
+        }
+    }
+
+    /// Queues an outline around part of a block, such as the shape box that was hit.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The block coordinates of the block.
+    /// * `shape` - The part of the block to outline, within `[0, 1]`.
+    /// * `color` - The RGBA color of the outline.
+    pub fn block(&mut self, position: [i32; 3], shape: &BlockBox, color: [f32; 4]) {
+        let origin = position.map(|value| value as f32);
+        self.boxes.push(HighlightBox {
+            min: std::array::from_fn(|i| origin[i] + shape.min[i] - HIGHLIGHT_MARGIN),
+            max: std::array::from_fn(|i| origin[i] + shape.max[i] + HIGHLIGHT_MARGIN),
+            color,
+        });
+    }
+
+    /// Queues an outline around a box of whole blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The block coordinates of the minimum corner block.
+    /// * `max` - The block coordinates of the maximum corner block, included in the box.
+    /// * `color` - The RGBA color of the outline.
+    pub fn region(&mut self, min: [i32; 3], max: [i32; 3], color: [f32; 4]) {
+        self.boxes.push(HighlightBox {
+            min: min.map(|value| value as f32 - HIGHLIGHT_MARGIN),
+            max: max.map(|value| value as f32 + 1.0 + HIGHLIGHT_MARGIN),
+            color,
+        });
+    }
+
+    /// Discards everything queued this frame without drawing it.
+    pub fn clear(&mut self) {
+        self.boxes.clear();
+    }
+
+    /// Draws all queued outlines and clears the queue.
+    ///
+    /// The projection and view matrices of the shader must already be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader to use for rendering, with per-vertex colors.
+    /// * `world` - A reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    pub fn draw(&mut self, chunk_shader: &Shader, world: &World, config: &Config) {
+        let thickness = config.ui.highlight_thickness;
+        if self.boxes.is_empty() || thickness <= 0.0 {
+            self.boxes.clear();
+            return;
+        }
+
+        let use_lines = thickness <= self.max_line_width;
+        self.vertices.clear();
+        for outline in self.boxes.iter() {
+            if use_lines {
+                Self::push_lines(&mut self.vertices, outline);
+            } else {
+                Self::push_edge_boxes(&mut self.vertices, outline, thickness);
+            }
+        }
+        self.boxes.clear();
+
+        // The outlines are built in block space
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("model", &Matrix4::from_scale(world.block_size));
+
+        unsafe {
+            // Colors may be translucent, such as the out of reach outline
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            if use_lines {
+                gl::LineWidth(thickness);
+                self.mesh.draw(&self.vertices, gl::LINES);
+                gl::LineWidth(1.0);
+            } else {
+                self.mesh.draw(&self.vertices, gl::TRIANGLES);
+            }
+
+            gl::Disable(gl::BLEND);
+        }
+    }
+
+    /// Appends the twelve edges of a box as lines.
+    fn push_lines(vertices: &mut Vec<f32>, outline: &HighlightBox) {
+        let corner = |offset: [usize; 3]| {
+            std::array::from_fn(|i| {
+                if offset[i] == 0 {
+                    outline.min[i]
+                } else {
+                    outline.max[i]
+                }
+            })
+        };
+
+        for a in 0..2 {
+            for b in 0..2 {
+                for (start, end) in [
+                    ([0, a, b], [1, a, b]),
+                    ([a, 0, b], [a, 1, b]),
+                    ([a, b, 0], [a, b, 1]),
+                ] {
+                    Self::push_vertex(vertices, corner(start), outline.color);
+                    Self::push_vertex(vertices, corner(end), outline.color);
+                }
+            }
+        }
+    }
+
+    /// Appends the twelve edges of a box as thin boxes of the given thickness in pixels.
+    fn push_edge_boxes(vertices: &mut Vec<f32>, outline: &HighlightBox, thickness: f32) {
+        let (min, max) = (outline.min, outline.max);
+        let half_edge = thickness * HIGHLIGHT_EDGE_PER_PIXEL / 2.0;
+
+        // Four edges run along each axis, one at each combination of the other two bounds
+        for axis in 0..3 {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            for u_bound in [min[u], max[u]] {
+                for v_bound in [min[v], max[v]] {
+                    let mut edge_min = [0.0; 3];
+                    let mut edge_max = [0.0; 3];
+                    edge_min[axis] = min[axis] - half_edge;
+                    edge_max[axis] = max[axis] + half_edge;
+                    edge_min[u] = u_bound - half_edge;
+                    edge_max[u] = u_bound + half_edge;
+                    edge_min[v] = v_bound - half_edge;
+                    edge_max[v] = v_bound + half_edge;
+
+                    for triangle in BOX_TRIANGLES.iter() {
+                        for offset in triangle {
+                            let position = std::array::from_fn(|i| {
+                                if offset[i] == 0 {
+                                    edge_min[i]
+                                } else {
+                                    edge_max[i]
+                                }
+                            });
+                            Self::push_vertex(vertices, position, outline.color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Appends a single vertex with the given position and color.
+    fn push_vertex(vertices: &mut Vec<f32>, position: [f32; 3], color: [f32; 4]) {
+        vertices.extend_from_slice(&[
+            position[0],
+            position[1],
+            position[2],
+            color[0],
+            color[1],
+            color[2],
+            color[3],
+        ]);
+    }
+}
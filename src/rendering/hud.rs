@@ -0,0 +1,162 @@
+use crate::rendering::gizmos::Gizmos;
+use crate::rendering::text::TextRenderer;
+use crate::world::block::Block;
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::world::diagnostics::WorldDiagnostics;
+use crate::world::world::World;
+use cgmath::Point3;
+
+/// The color of debug text.
+const DEBUG_COLOR: [f32; 4] = [1.0, 1.0, 0.6, 0.9];
+
+/// The color of the background behind debug text.
+const DEBUG_BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+/// The size of a font pixel in debug text, in screen pixels.
+const DEBUG_SCALE: f32 = 2.0;
+
+/// The distance between two lines of debug text, in screen pixels.
+const LINE_HEIGHT: f32 = 7.0 * DEBUG_SCALE;
+
+/// The top edge of the debug panel, below the fill tool label.
+const PANEL_TOP: f32 = 40.0;
+
+/// The outline colors of chunks in the re-mesh heat map, from barely rebuilt to hot.
+const HEAT_COLORS: ([f32; 4], [f32; 4]) = ([0.2, 0.5, 1.0, 0.3], [1.0, 0.1, 0.1, 1.0]);
+
+/// The rebuild heat at which a chunk is drawn fully red.
+const HOT_HEAT: f32 = 8.0;
+
+/// The rebuild heat below which a chunk is left out of the heat map.
+const MIN_SHOWN_HEAT: f32 = 0.1;
+
+/// The color of the selected block name.
+const BLOCK_NAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+
+/// The size of a font pixel in the selected block name, in screen pixels.
+const BLOCK_NAME_SCALE: f32 = 2.0;
+
+/// Queues the name of the selected block type to the lower left of the crosshair.
+///
+/// # Arguments
+///
+/// * `block_id` - The id of the selected block.
+/// * `text` - The text renderer to queue the name on.
+/// * `width` - The framebuffer width, in pixels.
+/// * `height` - The framebuffer height, in pixels.
+pub fn draw_block_name(block_id: u32, text: &mut TextRenderer, width: f32, height: f32) {
+    let name = Block::new(block_id).name();
+    // Glyphs are three font pixels wide with one pixel of spacing
+    let label_width = name.chars().count() as f32 * 4.0 * BLOCK_NAME_SCALE;
+    text.queue(
+        name,
+        width / 2.0 - 16.0 - label_width,
+        height / 2.0 + 16.0,
+        BLOCK_NAME_SCALE,
+        BLOCK_NAME_COLOR,
+    );
+}
+
+/// Queues lines of debug text on a dark background in the top-left corner.
+///
+/// # Arguments
+///
+/// * `lines` - The lines of text to show.
+/// * `text` - The text renderer to queue the panel on.
+pub fn draw_debug_panel(lines: &[String], text: &mut TextRenderer) {
+    let longest = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    text.queue_rect(
+        8.0,
+        PANEL_TOP - 8.0,
+        longest as f32 * 4.0 * DEBUG_SCALE + 16.0,
+        lines.len() as f32 * LINE_HEIGHT + 16.0 - 2.0 * DEBUG_SCALE,
+        DEBUG_BACKGROUND,
+    );
+
+    for (row, line) in lines.iter().enumerate() {
+        text.queue(
+            line,
+            16.0,
+            PANEL_TOP + row as f32 * LINE_HEIGHT,
+            DEBUG_SCALE,
+            DEBUG_COLOR,
+        );
+    }
+}
+
+/// Queues the world diagnostics and the mesh statistics of a chunk.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `coord` - The coordinates of the chunk to show.
+/// * `diagnostics` - The resources used by the loaded world.
+/// * `text` - The text renderer to queue the statistics on.
+pub fn draw_chunk_stats(
+    world: &World,
+    coord: (i32, i32),
+    diagnostics: &WorldDiagnostics,
+    text: &mut TextRenderer,
+) {
+    let mut lines = vec![
+        format!("LOADED CHUNKS {}", diagnostics.loaded_chunks),
+        format!("WORLD VERTICES {}", diagnostics.mesh_vertices),
+        format!("MEMORY {:.1} MIB", diagnostics.total_memory_mib()),
+        format!("PENDING MESHES {}", diagnostics.pending_meshes),
+        format!("CHUNK {} {}", coord.0, coord.1),
+    ];
+    match world.chunks.get(&coord) {
+        Some(chunk) => {
+            let stats = chunk.mesh_stats;
+            lines.push(format!("VERTICES {}", stats.vertex_count));
+            lines.push(format!("TRIANGLES {}", stats.triangle_count));
+            lines.push(format!(
+                "BUILD {:.2} MS",
+                stats.build_time.as_secs_f64() * 1000.0
+            ));
+            lines.push(format!("REMESH HEAT {:.1}", stats.heat()));
+            lines.push(format!("PALETTE {}", chunk.blocks.palette().len()));
+        }
+        None => lines.push(String::from("NOT LOADED")),
+    }
+
+    draw_debug_panel(&lines, text);
+}
+
+/// Queues an outline around every chunk that was re-meshed recently, colored from blue to
+/// red by how often, to find chunks that rebuild too often.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `gizmos` - The gizmos to queue the outlines on.
+pub fn draw_mesh_heat(world: &World, gizmos: &mut Gizmos) {
+    let (cold, hot) = HEAT_COLORS;
+    for (&(chunk_x, chunk_z), chunk) in world.chunks.iter() {
+        let heat = chunk.mesh_stats.heat();
+        if heat < MIN_SHOWN_HEAT {
+            continue;
+        }
+
+        let t = (heat / HOT_HEAT).min(1.0);
+        let color: [f32; 4] = std::array::from_fn(|i| cold[i] + (hot[i] - cold[i]) * t);
+
+        // Inset the outline a little so neighboring chunks don't draw over each other
+        let size = CHUNK_SIZE as f32;
+        let min = Point3::new(
+            chunk_x as f32 * size + 0.1,
+            0.0,
+            chunk_z as f32 * size + 0.1,
+        );
+        let max = Point3::new(
+            (chunk_x + 1) as f32 * size - 0.1,
+            CHUNK_HEIGHT as f32,
+            (chunk_z + 1) as f32 * size - 0.1,
+        );
+        gizmos.cuboid(min * world.block_size, max * world.block_size, color);
+    }
+}
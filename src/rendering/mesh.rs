@@ -34,7 +34,7 @@ impl Mesh {
             gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
             gl::BufferData(
                 gl::ARRAY_BUFFER,
-                (vertices.len() * size_of::<GLfloat>()) as GLsizeiptr,
+                size_of_val(vertices) as GLsizeiptr,
                 vertices.as_ptr() as *const _,
                 gl::STATIC_DRAW,
             );
@@ -57,6 +57,15 @@ impl Mesh {
         Mesh { vao, vertex_count }
     }
 
+    /// Creates a `Mesh` without any OpenGL objects, for tests that run without a context.
+    #[cfg(test)]
+    pub fn empty() -> Self {
+        Mesh {
+            vao: 0,
+            vertex_count: 0,
+        }
+    }
+
     /// Draws the mesh using OpenGL.
     pub fn draw(&self) {
         unsafe {
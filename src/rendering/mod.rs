@@ -1,2 +1,12 @@
+pub mod chunk_mesh;
+pub mod crosshair;
+pub mod dynamic_mesh;
+pub mod gizmos;
+pub mod highlight;
+pub mod hud;
 pub mod mesh;
+pub mod particles;
+pub mod screenshot;
 pub mod shader;
+pub mod shadow_map;
+pub mod text;
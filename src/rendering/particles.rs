@@ -0,0 +1,192 @@
+extern crate gl;
+
+use crate::config::Config;
+use crate::player::camera::Camera;
+use crate::rendering::dynamic_mesh::DynamicMesh;
+use crate::rendering::shader::Shader;
+use crate::world::generation::Rng;
+use cgmath::{InnerSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+/// How many particles a block break emits at a density of `1.0`.
+const BURST_COUNT: f32 = 12.0;
+
+/// The edge length of a particle, in blocks.
+const PARTICLE_SIZE: f32 = 0.12;
+
+/// The downward acceleration of particles, in blocks per second squared.
+const PARTICLE_GRAVITY: f32 = 16.0;
+
+/// The shortest and longest lifetime of a particle, in seconds.
+const LIFETIME_RANGE: (f32, f32) = (0.4, 0.9);
+
+/// Represents a single short-lived particle.
+struct Particle {
+    position: Point3<f32>,
+    velocity: Vector3<f32>,
+    color: [f32; 4],
+    size: f32,
+    age: f32,
+    lifetime: f32,
+}
+
+/// Simulates and draws small camera-facing quads, such as the debris of broken blocks.
+///
+/// Particles fall under gravity and fade out over their lifetime. The number of live
+/// particles is capped by `graphics.max_particles`.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    mesh: DynamicMesh,
+    vertices: Vec<f32>,
+    rng: Rng,
+}
+
+impl ParticleSystem {
+    /// Creates a new `ParticleSystem` with no particles.
+    ///
+    /// # Returns
+    ///
+    /// A new `ParticleSystem` instance.
+    pub fn new() -> Self {
+        ParticleSystem {
+            particles: Vec::new(),
+            mesh: DynamicMesh::new(),
+            vertices: Vec::new(),
+            rng: Rng::new(0x9E37_79B9),
+        }
+    }
+
+    /// Removes every particle, such as when the world they were emitted in is thrown away.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Emits a burst of particles from a broken block.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The world block coordinates of the broken block.
+    /// * `color` - The RGBA color of the broken block.
+    /// * `block_size` - The edge length of a block in world units.
+    /// * `config` - A reference to the game configuration.
+    pub fn burst(&mut self, position: [i32; 3], color: [f32; 4], block_size: f32, config: &Config) {
+        if !config.graphics.particles {
+            return;
+        }
+
+        let count = (BURST_COUNT * config.graphics.particle_density.max(0.0)).round() as usize;
+        for _ in 0..count {
+            if self.particles.len() >= config.graphics.max_particles {
+                break;
+            }
+
+            // Start somewhere inside the block and fly outward, mostly upward
+            let offset = Vector3::new(
+                self.rng.next_f32(),
+                self.rng.next_f32(),
+                self.rng.next_f32(),
+            );
+            let spread = offset - Vector3::new(0.5, 0.5, 0.5);
+            let velocity = Vector3::new(
+                spread.x * 4.0,
+                2.0 + self.rng.next_f32() * 3.0,
+                spread.z * 4.0,
+            );
+
+            // Vary the brightness a little so the burst doesn't look flat
+            let shade = 0.8 + self.rng.next_f32() * 0.2;
+            let lifetime =
+                LIFETIME_RANGE.0 + self.rng.next_f32() * (LIFETIME_RANGE.1 - LIFETIME_RANGE.0);
+
+            self.particles.push(Particle {
+                position: Point3::new(
+                    position[0] as f32 + offset.x,
+                    position[1] as f32 + offset.y,
+                    position[2] as f32 + offset.z,
+                ) * block_size,
+                velocity: velocity * block_size,
+                color: [
+                    color[0] * shade,
+                    color[1] * shade,
+                    color[2] * shade,
+                    color[3],
+                ],
+                size: PARTICLE_SIZE * block_size,
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Moves the particles and removes the ones that have faded out.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `block_size` - The edge length of a block in world units.
+    pub fn update(&mut self, delta_time: f32, block_size: f32) {
+        for particle in self.particles.iter_mut() {
+            particle.age += delta_time;
+            particle.velocity.y -= PARTICLE_GRAVITY * block_size * delta_time;
+            particle.position += particle.velocity * delta_time;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+    }
+
+    /// Draws all particles as quads facing the camera.
+    ///
+    /// The projection and view matrices of the shader must already be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader to use for rendering, with per-vertex colors.
+    /// * `camera` - A reference to the player's camera.
+    pub fn draw(&mut self, chunk_shader: &Shader, camera: &Camera) {
+        if self.particles.is_empty() {
+            return;
+        }
+
+        let right = camera.front.cross(camera.up).normalize();
+        let up = right.cross(camera.front).normalize();
+
+        self.vertices.clear();
+        for particle in self.particles.iter() {
+            let fade = 1.0 - particle.age / particle.lifetime;
+            let color = [
+                particle.color[0],
+                particle.color[1],
+                particle.color[2],
+                particle.color[3] * fade,
+            ];
+
+            let half_right = right * particle.size / 2.0;
+            let half_up = up * particle.size / 2.0;
+            let corners = [
+                particle.position - half_right - half_up,
+                particle.position + half_right - half_up,
+                particle.position + half_right + half_up,
+                particle.position - half_right + half_up,
+            ];
+            for corner in [0, 1, 2, 0, 2, 3] {
+                let point = corners[corner];
+                self.vertices.extend_from_slice(&[
+                    point.x, point.y, point.z, color[0], color[1], color[2], color[3],
+                ]);
+            }
+        }
+
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("model", &Matrix4::identity());
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+
+            self.mesh.draw(&self.vertices, gl::TRIANGLES);
+
+            gl::DepthMask(gl::TRUE);
+            gl::Disable(gl::BLEND);
+        }
+    }
+}
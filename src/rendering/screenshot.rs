@@ -0,0 +1,227 @@
+extern crate gl;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The signature every PNG file starts with.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// The largest amount of data in one uncompressed deflate block.
+const MAX_STORED_BLOCK: usize = 65535;
+
+/// The CRC-32 lookup table used for PNG chunk checksums.
+static CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+/// Saves the current contents of the framebuffer as a PNG file.
+///
+/// The file is named after the current time (UTC) and written to the given directory,
+/// which is created if it doesn't exist. Must be called before the buffers are swapped.
+///
+/// # Arguments
+///
+/// * `width` - The framebuffer width, in pixels.
+/// * `height` - The framebuffer height, in pixels.
+/// * `directory` - The directory to save the screenshot in.
+///
+/// # Returns
+///
+/// The path of the saved screenshot, or the error that prevented saving it.
+pub fn save_screenshot(width: i32, height: i32, directory: &str) -> io::Result<PathBuf> {
+    let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+    let mut pixels = vec![0u8; width * height * 4];
+    unsafe {
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            width as i32,
+            height as i32,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut _,
+        );
+    }
+
+    // OpenGL rows start at the bottom, PNG rows at the top. Each row starts with its filter
+    // type, which is always "none"
+    let mut image = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks_exact(width * 4).rev() {
+        image.push(0);
+        for pixel in row.chunks_exact(4) {
+            image.extend_from_slice(&pixel[..3]);
+        }
+    }
+
+    fs::create_dir_all(directory)?;
+    let path = Path::new(directory).join(format!("{}.png", timestamp()));
+    fs::write(&path, encode_png(width as u32, height as u32, &image))?;
+    Ok(path)
+}
+
+/// Encodes filtered RGB image data as a PNG file, without compression.
+fn encode_png(width: u32, height: u32, image: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGB, deflate, adaptive filtering, no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    // A zlib stream of stored deflate blocks
+    let mut data = vec![0x78, 0x01];
+    let mut blocks = image.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        data.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let length = block.len() as u16;
+        data.push(last as u8);
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend_from_slice(&(!length).to_le_bytes());
+        data.extend_from_slice(block);
+    }
+    data.extend_from_slice(&adler32(image).to_be_bytes());
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &data);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Appends a PNG chunk with its length and checksum.
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+/// Returns the CRC-32 checksum that ends a PNG chunk, computed over its type and data.
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+/// Returns the Adler-32 checksum that ends a zlib stream.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Returns the current UTC time as `YYYY-MM-DD_HH-MM-SS-mmm`, for file names.
+fn timestamp() -> String {
+    format_timestamp(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default(),
+    )
+}
+
+/// Formats a time since the Unix epoch as `YYYY-MM-DD_HH-MM-SS-mmm`.
+fn format_timestamp(now: Duration) -> String {
+    let seconds = now.as_secs();
+    let (time, days) = (seconds % 86400, (seconds / 86400) as i64);
+
+    // Convert days since 1970-01-01 into a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}-{:03}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        now.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn single_pixel_image_is_encoded() {
+        // A filter type byte followed by one red pixel
+        let png = encode_png(1, 1, &[0, 0xFF, 0, 0]);
+
+        let mut expected = PNG_SIGNATURE.to_vec();
+        expected.extend_from_slice(&[0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+        expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0]);
+        expected.extend_from_slice(&[0x90, 0x77, 0x53, 0xDE]);
+        expected.extend_from_slice(&[0, 0, 0, 15, b'I', b'D', b'A', b'T']);
+        // The zlib header, one final stored block of 4 bytes and the Adler-32 checksum
+        expected.extend_from_slice(&[0x78, 0x01, 1, 4, 0, 0xFB, 0xFF]);
+        expected.extend_from_slice(&[0, 0xFF, 0, 0, 0x03, 0x01, 0x01, 0x00]);
+        expected.extend_from_slice(&[0x8D, 0x1D, 0xE5, 0x82]);
+        expected.extend_from_slice(&[0, 0, 0, 0, b'I', b'E', b'N', b'D']);
+        expected.extend_from_slice(&[0xAE, 0x42, 0x60, 0x82]);
+        assert_eq!(png, expected);
+    }
+
+    #[test]
+    fn timestamps_cross_a_leap_day() {
+        let at = |seconds: u64, millis: u64| {
+            format_timestamp(Duration::from_millis(seconds * 1000 + millis))
+        };
+
+        assert_eq!(at(0, 0), "1970-01-01_00-00-00-000");
+        assert_eq!(at(1_709_164_799, 0), "2024-02-28_23-59-59-000");
+        assert_eq!(at(1_709_164_800, 0), "2024-02-29_00-00-00-000");
+        assert_eq!(at(1_709_251_199, 999), "2024-02-29_23-59-59-999");
+        assert_eq!(at(1_709_251_200, 0), "2024-03-01_00-00-00-000");
+        // 2100 is not a leap year
+        assert_eq!(at(4_107_456_000, 0), "2100-02-28_00-00-00-000");
+        assert_eq!(at(4_107_542_400, 0), "2100-03-01_00-00-00-000");
+    }
+}
@@ -1,5 +1,6 @@
 extern crate gl;
 
+use cgmath::{Matrix, Matrix4};
 use gl::types::*;
 use std::{ffi::CString, fs::File, io::Read, path::Path, ptr, str};
 
@@ -38,7 +39,7 @@ impl Shader {
 
     fn read_shader_source(path: &str) -> String {
         let path = Path::new(path);
-        let mut file = match File::open(&path) {
+        let mut file = match File::open(path) {
             Ok(file) => file,
             Err(why) => panic!("couldn't open {}: {}", path.display(), why),
         };
@@ -73,14 +74,15 @@ impl Shader {
             if status != (gl::TRUE as GLint) {
                 let mut len = 0;
                 gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-                let mut buf = Vec::with_capacity(len as usize);
-                buf.set_len((len as usize) - 1);
+                let mut buf = vec![0u8; len as usize];
                 gl::GetShaderInfoLog(
                     shader,
                     len,
                     ptr::null_mut(),
                     buf.as_mut_ptr() as *mut GLchar,
                 );
+                // Drop the nul terminator
+                buf.pop();
                 panic!(
                     "{}",
                     str::from_utf8(&buf).expect("ShaderInfoLog not valid utf8")
@@ -113,14 +115,15 @@ impl Shader {
             if status != (gl::TRUE as GLint) {
                 let mut len: GLint = 0;
                 gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
-                let mut buf = Vec::with_capacity(len as usize);
-                buf.set_len((len as usize) - 1);
+                let mut buf = vec![0u8; len as usize];
                 gl::GetProgramInfoLog(
                     program,
                     len,
                     ptr::null_mut(),
                     buf.as_mut_ptr() as *mut GLchar,
                 );
+                // Drop the nul terminator
+                buf.pop();
                 panic!(
                     "{}",
                     str::from_utf8(&buf).expect("ProgramInfoLog not valid utf8")
@@ -136,4 +139,77 @@ impl Shader {
             gl::UseProgram(self.id);
         }
     }
+
+    /// Returns the location of a uniform in the shader program.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uniform.
+    ///
+    /// # Returns
+    ///
+    /// The uniform location, or `-1` if the uniform does not exist.
+    fn uniform_location(&self, name: &str) -> GLint {
+        let c_name = CString::new(name).unwrap();
+        unsafe { gl::GetUniformLocation(self.id, c_name.as_ptr()) }
+    }
+
+    /// Sets a `mat4` uniform. The shader program must be in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uniform.
+    /// * `matrix` - The matrix value.
+    pub fn set_mat4(&self, name: &str, matrix: &Matrix4<f32>) {
+        unsafe {
+            gl::UniformMatrix4fv(
+                self.uniform_location(name),
+                1,
+                gl::FALSE as GLboolean,
+                matrix.as_ptr(),
+            );
+        }
+    }
+
+    /// Sets a `vec4` uniform. The shader program must be in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uniform.
+    /// * `value` - The vector value.
+    pub fn set_vec4(&self, name: &str, value: [f32; 4]) {
+        unsafe {
+            gl::Uniform4f(
+                self.uniform_location(name),
+                value[0],
+                value[1],
+                value[2],
+                value[3],
+            );
+        }
+    }
+
+    /// Sets a `float` uniform. The shader program must be in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uniform.
+    /// * `value` - The float value.
+    pub fn set_float(&self, name: &str, value: f32) {
+        unsafe {
+            gl::Uniform1f(self.uniform_location(name), value);
+        }
+    }
+
+    /// Sets an `int` (or sampler) uniform. The shader program must be in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uniform.
+    /// * `value` - The integer value.
+    pub fn set_int(&self, name: &str, value: i32) {
+        unsafe {
+            gl::Uniform1i(self.uniform_location(name), value);
+        }
+    }
 }
@@ -0,0 +1,216 @@
+extern crate gl;
+
+use crate::config::GraphicsConfig;
+use crate::rendering::shader::Shader;
+use crate::world::chunk::CHUNK_HEIGHT;
+use crate::world::world::World;
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Vector3, Vector4, ortho};
+use gl::types::*;
+use std::ptr;
+
+/// The direction sunlight travels in, pointing down and slightly sideways.
+const SUN_DIRECTION: Vector3<f32> = Vector3::new(-0.35, -1.0, -0.25);
+
+/// How much fully shadowed surfaces are darkened.
+const SHADOW_STRENGTH: f32 = 0.45;
+
+/// The texture unit the shadow map is bound to while drawing chunks.
+const SHADOW_TEXTURE_UNIT: u32 = 1;
+
+/// Renders the depth of the world as seen from the sun, so chunk meshes can be shadowed.
+///
+/// The shadow map covers a square of `graphics.shadow_distance` blocks around the player;
+/// everything outside of it is drawn fully lit.
+pub struct ShadowMap {
+    fbo: GLuint,
+    depth_texture: GLuint,
+    resolution: i32,
+    shader: Shader,
+    light_space: Matrix4<f32>,
+}
+
+impl ShadowMap {
+    /// Creates a new `ShadowMap` with the configured resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `graphics` - The graphics settings for shadows.
+    ///
+    /// # Returns
+    ///
+    /// A new `ShadowMap` instance.
+    pub fn new(graphics: &GraphicsConfig) -> Self {
+        let resolution = graphics.shadow_map_resolution.clamp(1, 8192) as i32;
+        let mut fbo: GLuint = 0;
+        let mut depth_texture: GLuint = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut depth_texture);
+            gl::BindTexture(gl::TEXTURE_2D, depth_texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::DEPTH_COMPONENT24 as GLint,
+                resolution,
+                resolution,
+                0,
+                gl::DEPTH_COMPONENT,
+                gl::FLOAT,
+                ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::TEXTURE_2D,
+                depth_texture,
+                0,
+            );
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                eprintln!("Shadow map framebuffer is incomplete; shadows may not render");
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        ShadowMap {
+            fbo,
+            depth_texture,
+            resolution,
+            shader: Shader::new(
+                "src/shaders/shadow_vertex_shader.glsl",
+                "src/shaders/shadow_fragment_shader.glsl",
+            ),
+            light_space: Matrix4::from_scale(1.0),
+        }
+    }
+
+    /// Renders the shadow casters around the player into the shadow map.
+    ///
+    /// The viewport is restored to the given framebuffer size afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A reference to the game world.
+    /// * `center` - The position the shadowed area is centered on, usually the camera.
+    /// * `graphics` - The graphics settings for shadows.
+    /// * `framebuffer_size` - The width and height of the window framebuffer.
+    pub fn render(
+        &mut self,
+        world: &World,
+        center: Point3<f32>,
+        graphics: &GraphicsConfig,
+        framebuffer_size: (i32, i32),
+    ) {
+        if !graphics.shadows {
+            return;
+        }
+
+        let distance = graphics.shadow_distance.max(1.0) * world.block_size;
+        self.light_space = self.light_space_matrix(center, distance, world.block_size);
+
+        self.shader.use_program();
+        self.shader.set_mat4("lightSpace", &self.light_space);
+
+        unsafe {
+            gl::Viewport(0, 0, self.resolution, self.resolution);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+
+            // Push the depth back a little to reduce shadow acne on lit faces
+            gl::Enable(gl::POLYGON_OFFSET_FILL);
+            gl::PolygonOffset(2.0, 4.0);
+
+            world.draw_shadow_casters(&self.shader, center, distance);
+
+            gl::Disable(gl::POLYGON_OFFSET_FILL);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, framebuffer_size.0, framebuffer_size.1);
+        }
+    }
+
+    /// Binds the shadow map for drawing with the chunk shader, or disables shadows on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader used for rendering chunk meshes.
+    /// * `graphics` - The graphics settings for shadows.
+    pub fn bind(&self, chunk_shader: &Shader, graphics: &GraphicsConfig) {
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("lightSpace", &self.light_space);
+        chunk_shader.set_int("shadowMap", SHADOW_TEXTURE_UNIT as i32);
+        chunk_shader.set_float(
+            "shadowStrength",
+            if graphics.shadows {
+                SHADOW_STRENGTH
+            } else {
+                0.0
+            },
+        );
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + SHADOW_TEXTURE_UNIT);
+            gl::BindTexture(gl::TEXTURE_2D, self.depth_texture);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
+
+    /// Returns the matrix projecting world positions into the shadow map.
+    fn light_space_matrix(
+        &self,
+        center: Point3<f32>,
+        distance: f32,
+        block_size: f32,
+    ) -> Matrix4<f32> {
+        let direction = SUN_DIRECTION.normalize();
+        let view = Matrix4::look_at_rh(
+            Point3::origin(),
+            Point3::from_vec(direction),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+
+        // Snap the center to whole texels, so shadow edges don't shimmer as the player moves
+        let texel = 2.0 * distance / self.resolution as f32;
+        let light_center = view * Vector4::new(center.x, center.y, center.z, 1.0);
+        let x = (light_center.x / texel).round() * texel;
+        let y = (light_center.y / texel).round() * texel;
+
+        // Include the full height of the world in front of and behind the center
+        let depth = distance + CHUNK_HEIGHT as f32 * block_size;
+        let projection = ortho(
+            x - distance,
+            x + distance,
+            y - distance,
+            y + distance,
+            -light_center.z - depth,
+            -light_center.z + depth,
+        );
+
+        projection * view
+    }
+}
+
+impl Drop for ShadowMap {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.depth_texture);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+extern crate gl;
+
+use crate::rendering::dynamic_mesh::DynamicMesh;
+use crate::rendering::shader::Shader;
+use cgmath::{Matrix4, SquareMatrix, ortho};
+
+/// The width of a glyph, in font pixels.
+const GLYPH_WIDTH: usize = 3;
+
+/// The height of a glyph, in font pixels.
+const GLYPH_HEIGHT: usize = 5;
+
+/// Draws text on screen using a built-in 3x5 pixel font.
+///
+/// Text is queued during the frame in screen pixel coordinates (origin at the top-left
+/// corner) and drawn in a single batch on top of the scene.
+pub struct TextRenderer {
+    mesh: DynamicMesh,
+    vertices: Vec<f32>,
+}
+
+impl TextRenderer {
+    /// Creates a new `TextRenderer` with no queued text.
+    ///
+    /// # Returns
+    ///
+    /// A new `TextRenderer` instance.
+    pub fn new() -> Self {
+        TextRenderer {
+            mesh: DynamicMesh::new(),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Queues a line of text to be drawn this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to draw. Lowercase letters are drawn as uppercase.
+    /// * `x` - The left edge of the text, in screen pixels.
+    /// * `y` - The top edge of the text, in screen pixels.
+    /// * `scale` - The size of a font pixel, in screen pixels.
+    /// * `color` - The RGBA color of the text.
+    pub fn queue(&mut self, text: &str, x: f32, y: f32, scale: f32, color: [f32; 4]) {
+        let mut cursor_x = x;
+        for character in text.chars() {
+            let rows = glyph(character.to_ascii_uppercase());
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                        continue;
+                    }
+
+                    let left = cursor_x + column as f32 * scale;
+                    let top = y + row as f32 * scale;
+                    self.queue_rect(left, top, scale, scale, color);
+                }
+            }
+            cursor_x += (GLYPH_WIDTH + 1) as f32 * scale;
+        }
+    }
+
+    /// Queues a filled rectangle, for backgrounds and bars drawn alongside text.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The left edge of the rectangle, in screen pixels.
+    /// * `y` - The top edge of the rectangle, in screen pixels.
+    /// * `width` - The width of the rectangle, in screen pixels.
+    /// * `height` - The height of the rectangle, in screen pixels.
+    /// * `color` - The RGBA color of the rectangle.
+    pub fn queue_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: [f32; 4]) {
+        let corners = [
+            [x, y],
+            [x + width, y],
+            [x + width, y + height],
+            [x, y],
+            [x + width, y + height],
+            [x, y + height],
+        ];
+        for [corner_x, corner_y] in corners {
+            self.vertices.extend_from_slice(&[
+                corner_x, corner_y, 0.0, color[0], color[1], color[2], color[3],
+            ]);
+        }
+    }
+
+    /// Discards everything queued this frame without drawing it.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Draws all queued text and clears the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_shader` - The shader to use for rendering, with per-vertex colors.
+    /// * `width` - The framebuffer width, in pixels.
+    /// * `height` - The framebuffer height, in pixels.
+    pub fn draw(&mut self, chunk_shader: &Shader, width: f32, height: f32) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        chunk_shader.use_program();
+        chunk_shader.set_mat4("projection", &ortho(0.0, width, height, 0.0, -1.0, 1.0));
+        chunk_shader.set_mat4("view", &Matrix4::identity());
+        chunk_shader.set_mat4("model", &Matrix4::identity());
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            self.mesh.draw(&self.vertices, gl::TRIANGLES);
+
+            gl::Disable(gl::BLEND);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+
+        self.vertices.clear();
+    }
+}
+
+/// Returns the rows of a glyph, top to bottom, with the leftmost pixel in the highest bit.
+fn glyph(character: char) -> [u8; GLYPH_HEIGHT] {
+    match character {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        '[' => [0b110, 0b100, 0b100, 0b100, 0b110],
+        ']' => [0b011, 0b001, 0b001, 0b001, 0b011],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010], // Unknown (question mark)
+    }
+}
@@ -0,0 +1,92 @@
+#version 330 core
+in vec4 vertexColor;
+in vec3 blockPos;
+in vec4 lightSpacePos;
+in float viewDistance;
+out vec4 FragColor;
+
+// The distances where fog starts and where it fully hides the terrain (fogEnd 0 disables fog)
+uniform float fogStart;
+uniform float fogEnd;
+uniform vec4 fogColor;
+
+// The depth of the scene as seen from the sun
+uniform sampler2D shadowMap;
+
+// How much shadowed surfaces are darkened (0 disables shadows)
+uniform float shadowStrength;
+
+// How much block edges are darkened (0 disables the outline)
+uniform float outlineStrength;
+
+// The display gamma; values above 1 brighten dark areas
+uniform float gamma;
+
+// The width of the outline, as a fraction of a block
+const float OUTLINE_WIDTH = 0.03;
+
+// Depth offset that keeps surfaces from shadowing themselves
+const float SHADOW_BIAS = 0.002;
+
+// Returns how much of the fragment is in shadow, from 0 (lit) to 1 (fully shadowed)
+float shadowAmount() {
+    vec3 coords = lightSpacePos.xyz / lightSpacePos.w * 0.5 + 0.5;
+
+    // Everything outside the shadow distance is lit
+    if (coords.x < 0.0 || coords.x > 1.0 || coords.y < 0.0 || coords.y > 1.0 || coords.z > 1.0) {
+        return 0.0;
+    }
+
+    // Average a 3x3 neighborhood so shadow edges aren't jagged
+    vec2 texel = 1.0 / vec2(textureSize(shadowMap, 0));
+    float shadow = 0.0;
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            float depth = texture(shadowMap, coords.xy + vec2(x, y) * texel).r;
+            shadow += coords.z - SHADOW_BIAS > depth ? 1.0 : 0.0;
+        }
+    }
+    return shadow / 9.0;
+}
+
+// Fades the fragment into the fog color with distance
+void applyFog() {
+    if (fogEnd <= 0.0) {
+        return;
+    }
+    float fog = smoothstep(fogStart, fogEnd, viewDistance);
+    FragColor.rgb = mix(FragColor.rgb, fogColor.rgb, fog);
+}
+
+// Fades in fog, then applies the gamma adjustment as the last step
+void finishColor() {
+    applyFog();
+    if (gamma > 0.0) {
+        FragColor.rgb = pow(FragColor.rgb, vec3(1.0 / gamma));
+    }
+}
+
+void main() {
+    FragColor = vertexColor;
+    if (shadowStrength > 0.0) {
+        FragColor.rgb *= 1.0 - shadowStrength * shadowAmount();
+    }
+    if (outlineStrength <= 0.0) {
+        finishColor();
+        return;
+    }
+
+    // Distance to the nearest block boundary along each axis. On a face, the smallest
+    // distance belongs to the face itself, so the middle one is the distance to an edge
+    vec3 boundary = min(fract(blockPos), 1.0 - fract(blockPos));
+    float smallest = min(boundary.x, min(boundary.y, boundary.z));
+    float largest = max(boundary.x, max(boundary.y, boundary.z));
+    float edge = boundary.x + boundary.y + boundary.z - smallest - largest;
+
+    // Fade the outline over about a pixel, so distant edges don't shimmer
+    float blur = fwidth(edge);
+    float outline = 1.0 - smoothstep(OUTLINE_WIDTH - blur, OUTLINE_WIDTH + blur, edge);
+    FragColor.rgb *= 1.0 - outlineStrength * outline;
+    finishColor();
+}
+
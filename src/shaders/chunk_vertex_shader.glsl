@@ -0,0 +1,23 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec4 aColor;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+uniform mat4 lightSpace;
+
+out vec4 vertexColor;
+out vec3 blockPos;
+out vec4 lightSpacePos;
+out float viewDistance;
+
+void main() {
+    vec4 worldPos = model * vec4(aPos, 1.0);
+    vec4 viewPos = view * worldPos;
+    gl_Position = projection * viewPos;
+    viewDistance = length(viewPos.xyz);
+    vertexColor = aColor;
+    blockPos = aPos;
+    lightSpacePos = lightSpace * worldPos;
+}
@@ -1,8 +1,24 @@
 #version 330 core
+in float viewDistance;
 out vec4 FragColor;
 
 uniform vec4 color;
 
+// The distances where fog starts and where it fully hides the scene (fogEnd 0 disables fog)
+uniform float fogStart;
+uniform float fogEnd;
+uniform vec4 fogColor;
+
+// The display gamma; values above 1 brighten dark areas
+uniform float gamma;
+
 void main() {
     FragColor = color;
+    if (fogEnd > 0.0) {
+        float fog = smoothstep(fogStart, fogEnd, viewDistance);
+        FragColor.rgb = mix(FragColor.rgb, fogColor.rgb, fog);
+    }
+    if (gamma > 0.0) {
+        FragColor.rgb = pow(FragColor.rgb, vec3(1.0 / gamma));
+    }
 }
\ No newline at end of file
@@ -5,6 +5,10 @@ uniform mat4 model;
 uniform mat4 view;
 uniform mat4 projection;
 
+out float viewDistance;
+
 void main() {
-    gl_Position = projection * view * model * vec4(aPos, 1.0);
+    vec4 viewPos = view * model * vec4(aPos, 1.0);
+    gl_Position = projection * viewPos;
+    viewDistance = length(viewPos.xyz);
 }
\ No newline at end of file
@@ -1,3 +1,69 @@
+/// An axis-aligned box inside a block cell, in block-local coordinates from `0` to `1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl BlockBox {
+    /// A box filling the whole cell.
+    pub const FULL: BlockBox = BlockBox {
+        min: [0.0, 0.0, 0.0],
+        max: [1.0, 1.0, 1.0],
+    };
+
+    /// Returns the center of the box, in block-local coordinates.
+    pub fn center(&self) -> [f32; 3] {
+        [
+            (self.min[0] + self.max[0]) / 2.0,
+            (self.min[1] + self.max[1]) / 2.0,
+            (self.min[2] + self.max[2]) / 2.0,
+        ]
+    }
+
+    /// Returns the edge lengths of the box, in blocks.
+    pub fn size(&self) -> [f32; 3] {
+        [
+            self.max[0] - self.min[0],
+            self.max[1] - self.min[1],
+            self.max[2] - self.min[2],
+        ]
+    }
+}
+
+/// The water level of a source block; flowing water has a lower level.
+pub const MAX_WATER_LEVEL: u8 = 8;
+
+/// The id of flowing water at level 1; flowing water at level `n` has id `FLOWING_WATER + n - 1`.
+const FLOWING_WATER: u32 = 11;
+
+/// The shapes of flowing water, indexed by level minus one. Each level is an eighth of a block.
+static FLOWING_WATER_BOXES: [[BlockBox; 1]; 7] = {
+    let mut boxes = [[BlockBox::FULL]; 7];
+    let mut level = 0;
+    while level < 7 {
+        boxes[level][0].max[1] = (level + 1) as f32 / MAX_WATER_LEVEL as f32;
+        level += 1;
+    }
+    boxes
+};
+
+/// The log standing upright, along the Y axis.
+pub const LOG_Y: u32 = 6;
+
+/// The log lying along the X axis.
+pub const LOG_X: u32 = 7;
+
+/// The log lying along the Z axis.
+pub const LOG_Z: u32 = 8;
+
+/// The variants of the log, indexed by the axis they are aligned to (0 = X, 1 = Y, 2 = Z).
+const LOG_VARIANTS: [u32; 3] = [LOG_X, LOG_Y, LOG_Z];
+
+/// How far the player must look up or down, as the vertical part of the view direction,
+/// for a directional block placed on a top or bottom face to stand upright.
+const UPRIGHT_PITCH: f32 = 0.5;
+
 /// Represents a block in the world.
 #[derive(Clone, Copy, Debug)]
 pub struct Block {
@@ -25,16 +91,252 @@ impl Block {
     /// An array of four `f32` values representing the RGBA color of the block.
     pub fn get_color(&self) -> [f32; 4] {
         match self.id {
-            0 => [0.0, 0.0, 0.0, 0.0], // Air/empty (transparent)
-            1 => [0.6, 0.3, 0.0, 1.0], // Dirt/soil (brown)
-            2 => [0.0, 0.7, 0.0, 1.0], // Grass (green)
-            3 => [0.5, 0.5, 0.5, 1.0], // Stone (gray)
-            4 => [0.9, 0.9, 0.9, 1.0], // Snow (white)
-            5 => [0.0, 0.0, 0.8, 1.0], // Water (blue)
-            _ => [1.0, 0.0, 1.0, 1.0], // Unknown (magenta)
+            0 => [0.0, 0.0, 0.0, 0.0],           // Air/empty (transparent)
+            1 => [0.6, 0.3, 0.0, 1.0],           // Dirt/soil (brown)
+            2 => [0.0, 0.7, 0.0, 1.0],           // Grass (green)
+            3 => [0.5, 0.5, 0.5, 1.0],           // Stone (gray)
+            4 => [0.9, 0.9, 0.9, 1.0],           // Snow (white)
+            5 | 11..=17 => [0.0, 0.0, 0.8, 1.0], // Water (blue)
+            6..=8 => [0.4, 0.25, 0.1, 1.0],      // Log bark (dark brown)
+            9 => [0.55, 0.55, 0.55, 1.0],        // Stone slab (light gray)
+            10 => [1.0, 0.85, 0.3, 1.0],         // Torch (yellow)
+            18 => [0.75, 0.9, 0.95, 0.35],       // Glass (pale blue, translucent)
+            19 => [0.35, 0.8, 0.25, 1.0],        // Tall grass (light green)
+            20 => [0.15, 0.5, 0.15, 1.0],        // Leaves (dark green)
+            _ => [1.0, 0.0, 1.0, 1.0],           // Unknown (magenta)
+        }
+    }
+
+    /// Returns the display name of the block, shared by all of its variants such as water
+    /// levels and log orientations.
+    pub fn name(&self) -> &'static str {
+        match self.id {
+            0 => "Air",
+            1 => "Dirt",
+            2 => "Grass",
+            3 => "Stone",
+            4 => "Snow",
+            5 | 11..=17 => "Water",
+            6..=8 => "Log",
+            9 => "Stone slab",
+            10 => "Torch",
+            18 => "Glass",
+            19 => "Tall grass",
+            20 => "Leaves",
+            _ => "Unknown",
+        }
+    }
+
+    /// Returns the color of one face of the block.
+    ///
+    /// Directional blocks such as logs show a different color on the faces along their axis.
+    ///
+    /// # Arguments
+    ///
+    /// * `normal` - The outward normal of the face.
+    ///
+    /// # Returns
+    ///
+    /// An array of four `f32` values representing the RGBA color of the face.
+    pub fn get_face_color(&self, normal: [i32; 3]) -> [f32; 4] {
+        match self.get_axis() {
+            Some(axis) if normal[axis] != 0 => [0.75, 0.6, 0.35, 1.0], // Log rings (light brown)
+            _ => self.get_color(),
+        }
+    }
+
+    /// Returns the boxes that make up the shape of the block.
+    ///
+    /// Most blocks fill their whole cell, while partial blocks such as slabs are made of one
+    /// or more smaller boxes. The shape is used for meshing, raycasting, and the highlight.
+    ///
+    /// # Returns
+    ///
+    /// The boxes of the block, or an empty slice for air.
+    pub fn collision_boxes(&self) -> &'static [BlockBox] {
+        match self.id {
+            0 => &[], // Air
+            9 => &[BlockBox {
+                min: [0.0, 0.0, 0.0],
+                max: [1.0, 0.5, 1.0],
+            }], // Stone slab (bottom half)
+            10 => &[BlockBox {
+                min: [0.4375, 0.0, 0.4375],
+                max: [0.5625, 0.625, 0.5625],
+            }], // Torch (thin post)
+            19 => &[BlockBox {
+                min: [0.2, 0.0, 0.2],
+                max: [0.8, 0.75, 0.8],
+            }], // Tall grass (tuft)
+            11..=17 => &FLOWING_WATER_BOXES[(self.id - FLOWING_WATER) as usize], // Flowing water
+            _ => &[BlockBox::FULL],
+        }
+    }
+
+    /// Returns whether the block fills its whole cell, hiding the faces of its neighbors.
+    pub fn is_full_cube(&self) -> bool {
+        self.collision_boxes() == [BlockBox::FULL]
+    }
+
+    /// Returns whether the player collides with the block.
+    ///
+    /// Decorative blocks such as tall grass and torches can be walked through, but are still
+    /// drawn and can be targeted and broken.
+    pub fn is_solid(&self) -> bool {
+        !matches!(self.id, 0 | 10 | 19) // Air, torch, tall grass
+    }
+
+    /// Returns whether blocks behind this one can be seen through it, such as water.
+    pub fn is_transparent(&self) -> bool {
+        self.water_level() > 0 || self.id == 18
+    }
+
+    /// Returns whether a full neighboring block hides the face of this block it touches.
+    ///
+    /// Opaque neighbors hide every face. Transparent neighbors only hide faces of the same
+    /// kind of block, so solid faces under water are drawn but the inside of a pool isn't.
+    ///
+    /// # Arguments
+    ///
+    /// * `neighbor` - The block on the other side of the face.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the face can be skipped when meshing.
+    pub fn is_face_hidden_by(&self, neighbor: &Block) -> bool {
+        if !neighbor.is_solid() || !neighbor.is_full_cube() {
+            return false;
+        }
+        if !neighbor.is_transparent() {
+            return true;
+        }
+        self.is_transparent()
+            && (self.id == neighbor.id || (self.water_level() > 0 && neighbor.water_level() > 0))
+    }
+
+    /// Returns how long the block takes to break.
+    ///
+    /// # Returns
+    ///
+    /// The break time in seconds, or `0` for blocks that break instantly.
+    pub fn hardness(&self) -> f32 {
+        match self.id {
+            1 => 0.5,           // Dirt
+            2 => 0.6,           // Grass
+            3 => 1.5,           // Stone
+            4 => 0.2,           // Snow
+            5 | 11..=17 => 0.1, // Water
+            6..=8 => 1.0,       // Log
+            9 => 1.2,           // Stone slab
+            10 => 0.0,          // Torch
+            18 => 0.3,          // Glass
+            19 => 0.0,          // Tall grass
+            20 => 0.2,          // Leaves
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the water level of the block.
+    ///
+    /// # Returns
+    ///
+    /// `MAX_WATER_LEVEL` for a water source, `1` to `MAX_WATER_LEVEL - 1` for flowing water, or
+    /// `0` for blocks that aren't water.
+    pub fn water_level(&self) -> u8 {
+        match self.id {
+            5 => MAX_WATER_LEVEL,                           // Water source
+            11..=17 => (self.id - FLOWING_WATER + 1) as u8, // Flowing water
+            _ => 0,
+        }
+    }
+
+    /// Returns the block id of water at the given level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The water level, from `1` to `MAX_WATER_LEVEL`.
+    ///
+    /// # Returns
+    ///
+    /// The id of flowing water, or of a water source at `MAX_WATER_LEVEL`.
+    pub fn water(level: u8) -> u32 {
+        if level >= MAX_WATER_LEVEL {
+            5
+        } else {
+            FLOWING_WATER + level.max(1) as u32 - 1
+        }
+    }
+
+    /// Returns whether the block is a log, in any orientation.
+    pub fn is_log(&self) -> bool {
+        LOG_VARIANTS.contains(&self.id)
+    }
+
+    /// Returns the block light level the block emits.
+    ///
+    /// # Returns
+    ///
+    /// The emitted light level, from `0` (none) to `lighting::MAX_LIGHT`.
+    pub fn light_emission(&self) -> u8 {
+        match self.id {
+            10 => 14, // Torch
+            _ => 0,
         }
     }
 
+    /// Returns the axis a directional block is aligned to.
+    ///
+    /// # Returns
+    ///
+    /// The axis index (0 = X, 1 = Y, 2 = Z), or `None` if the block has no orientation.
+    pub fn get_axis(&self) -> Option<usize> {
+        self.axis_variants()?.iter().position(|&id| id == self.id)
+    }
+
+    /// Returns the variants of a directional block, indexed by the axis they are aligned to.
+    ///
+    /// # Returns
+    ///
+    /// The block ids of the variants, or `None` if the block has no orientation.
+    fn axis_variants(&self) -> Option<&'static [u32; 3]> {
+        match self.id {
+            6..=8 => Some(&LOG_VARIANTS), // Log
+            _ => None,
+        }
+    }
+
+    /// Returns the block id to place against a face, given the direction the player faces.
+    ///
+    /// Directional blocks placed against a side face point along the face normal. On top
+    /// and bottom faces they stand upright when the player looks steeply up or down, and
+    /// otherwise lie along the horizontal axis the player faces the most. This is the
+    /// orientation shown by the placement ghost. Other blocks are returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `normal` - The normal of the face being placed against.
+    /// * `facing` - The direction the player is looking in.
+    ///
+    /// # Returns
+    ///
+    /// The oriented block id.
+    pub fn oriented(&self, normal: [i32; 3], facing: [f32; 3]) -> u32 {
+        let Some(variants) = self.axis_variants() else {
+            return self.id;
+        };
+
+        let axis = if normal[1] == 0 {
+            if normal[0] != 0 { 0 } else { 2 }
+        } else if facing[1].abs() >= UPRIGHT_PITCH {
+            1
+        } else if facing[0].abs() > facing[2].abs() {
+            0
+        } else {
+            2
+        };
+        variants[axis]
+    }
+
     /// Returns the vertices for a cube.
     ///
     /// # Returns
@@ -87,3 +389,41 @@ impl Block {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Looking level along the Z axis.
+    const LEVEL: [f32; 3] = [0.0, 0.0, -1.0];
+
+    #[test]
+    fn logs_on_x_faces_lie_along_x() {
+        let log = Block::new(LOG_Y);
+        assert_eq!(log.oriented([1, 0, 0], LEVEL), LOG_X);
+        assert_eq!(log.oriented([-1, 0, 0], LEVEL), LOG_X);
+        // Looking along X at a top face lays the log along X too
+        assert_eq!(log.oriented([0, 1, 0], [0.9, -0.3, 0.3]), LOG_X);
+    }
+
+    #[test]
+    fn logs_looked_down_on_stand_upright() {
+        let log = Block::new(LOG_X);
+        assert_eq!(log.oriented([0, 1, 0], [0.3, -0.9, 0.3]), LOG_Y);
+        assert_eq!(log.oriented([0, -1, 0], [0.0, 0.8, 0.6]), LOG_Y);
+        assert_eq!(Block::new(LOG_Y).get_axis(), Some(1));
+    }
+
+    #[test]
+    fn logs_on_z_faces_lie_along_z() {
+        let log = Block::new(LOG_X);
+        assert_eq!(log.oriented([0, 0, 1], [1.0, 0.0, 0.0]), LOG_Z);
+        assert_eq!(log.oriented([0, 0, -1], LEVEL), LOG_Z);
+        assert_eq!(log.oriented([0, 1, 0], [0.3, -0.3, -0.9]), LOG_Z);
+    }
+
+    #[test]
+    fn blocks_without_an_axis_keep_their_id() {
+        assert_eq!(Block::new(3).oriented([1, 0, 0], LEVEL), 3);
+    }
+}
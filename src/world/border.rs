@@ -0,0 +1,165 @@
+use crate::config::WorldConfig;
+use crate::rendering::gizmos::Gizmos;
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::world::world::World;
+use cgmath::Point3;
+
+/// How fast a player outside the border is pushed back in, in blocks per second.
+const PUSH_BACK_SPEED: f32 = 4.0;
+
+/// How close the player has to be to the border for it to be drawn, in blocks.
+const VIEW_DISTANCE: f32 = 16.0;
+
+/// The color of the border grid when the player touches it; it fades out with distance.
+const BORDER_COLOR: [f32; 4] = [1.0, 0.3, 0.2, 0.6];
+
+/// A square boundary centered on the origin that the world ends at.
+///
+/// Chunks entirely outside the border are never loaded, and the player is kept inside it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBorder {
+    /// The distance from the origin to each side of the border, in blocks.
+    pub size: i32,
+}
+
+impl WorldBorder {
+    /// Returns the border set in the world configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The world configuration.
+    ///
+    /// # Returns
+    ///
+    /// The world border, or `None` if the world is endless.
+    pub fn new(config: &WorldConfig) -> Option<Self> {
+        config.border.map(|size| WorldBorder {
+            size: size.min(i32::MAX as u32) as i32,
+        })
+    }
+
+    /// Returns whether any column of a chunk lies inside the border.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The coordinates of the chunk.
+    pub fn contains_chunk(&self, coord: (i32, i32)) -> bool {
+        let size = CHUNK_SIZE as i64;
+        let overlaps = |chunk: i32| {
+            chunk as i64 * size < self.size as i64 && (chunk as i64 + 1) * size > -self.size as i64
+        };
+        overlaps(coord.0) && overlaps(coord.1)
+    }
+
+    /// Moves a block column inside the border, if it's outside.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world X coordinate of the column.
+    /// * `z` - The world Z coordinate of the column.
+    ///
+    /// # Returns
+    ///
+    /// The nearest column inside the border.
+    pub fn clamp_column(&self, x: i32, z: i32) -> (i32, i32) {
+        let (min, max) = (-self.size, (self.size - 1).max(-self.size));
+        (x.clamp(min, max), z.clamp(min, max))
+    }
+
+    /// Keeps a moving player inside the border.
+    ///
+    /// The border acts as a soft wall: the player can't move further out than they already
+    /// are, and a player outside it drifts back in instead of being teleported.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The position of the player before moving, in world units.
+    /// * `target` - The position the player wants to move to, in world units.
+    /// * `radius` - The collision radius of the player.
+    /// * `block_size` - The size of a block, in world units.
+    /// * `delta_time` - The time elapsed since the last frame.
+    ///
+    /// # Returns
+    ///
+    /// The target position, limited by the border.
+    pub fn push_back(
+        &self,
+        current: Point3<f32>,
+        target: Point3<f32>,
+        radius: f32,
+        block_size: f32,
+        delta_time: f32,
+    ) -> Point3<f32> {
+        let high = self.size as f32 * block_size - radius;
+        let low = -high;
+        let push = PUSH_BACK_SPEED * block_size * delta_time;
+
+        let limit = |previous: f32, value: f32| {
+            if value > high {
+                (value.min(previous.max(high)) - push).max(high)
+            } else if value < low {
+                (value.max(previous.min(low)) + push).min(low)
+            } else {
+                value
+            }
+        };
+
+        Point3::new(
+            limit(current.x, target.x),
+            target.y,
+            limit(current.z, target.z),
+        )
+    }
+
+    /// Queues a translucent grid on each side of the border the player is near.
+    ///
+    /// The grid fades in as the player approaches the border.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A reference to the game world.
+    /// * `player` - The player's position, in world units.
+    /// * `gizmos` - The gizmos to queue the grid on.
+    pub fn draw(&self, world: &World, player: Point3<f32>, gizmos: &mut Gizmos) {
+        let center = world.to_block_space(player);
+        let extent = VIEW_DISTANCE as i32;
+        let min_y = (center.y.floor() as i32 - extent).max(0);
+        let max_y = (center.y.floor() as i32 + extent).min(CHUNK_HEIGHT as i32);
+        let size = self.size as f32;
+
+        // Each side is given by the axis it's perpendicular to and its coordinate on it
+        for (across, along, coordinate) in
+            [(0, 2, size), (0, 2, -size), (2, 0, size), (2, 0, -size)]
+        {
+            let distance = ([center.x, center.y, center.z][across] - coordinate).abs();
+            if distance > VIEW_DISTANCE {
+                continue;
+            }
+            let mut color = BORDER_COLOR;
+            color[3] *= 1.0 - distance / VIEW_DISTANCE;
+
+            // Only draw the part of the side that is inside the border
+            let center_along = [center.x, center.y, center.z][along].floor() as i32;
+            let min_along = (center_along - extent).max(-self.size);
+            let max_along = (center_along + extent).min(self.size);
+            if min_along > max_along {
+                continue;
+            }
+
+            let point = |horizontal: i32, y: i32| {
+                let mut block = [0.0; 3];
+                block[across] = coordinate;
+                block[along] = horizontal as f32;
+                block[1] = y as f32;
+                Point3::new(block[0], block[1], block[2]) * world.block_size
+            };
+
+            for horizontal in min_along..=max_along {
+                gizmos.line(point(horizontal, min_y), point(horizontal, max_y), color);
+            }
+            for y in min_y..=max_y {
+                gizmos.line(point(min_along, y), point(max_along, y), color);
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use crate::rendering::chunk_mesh::ChunkMesh;
+use std::time::{Duration, Instant};
+
+/// The width and depth of a chunk, in blocks.
+///
+/// Any positive size works; powers of two let block coordinates be split into chunk and
+/// local coordinates with shifts and masks instead of divisions.
+pub const CHUNK_SIZE: usize = 16;
+
+// Chunk coordinate math divides by the chunk size
+const _: () = assert!(CHUNK_SIZE > 0, "CHUNK_SIZE must be positive");
+
+/// The height of a chunk, in blocks. Chunks span the full height of the world.
+pub const CHUNK_HEIGHT: usize = 32;
+
+/// The number of blocks in a chunk.
+const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_HEIGHT * CHUNK_SIZE;
+
+// Every palette entry is used by a block, plus the one being added while a block is set,
+// and all of them must fit in the two-byte indices
+const _: () = assert!(
+    CHUNK_VOLUME <= u16::MAX as usize,
+    "a chunk must have fewer blocks than a u16 can index"
+);
+
+/// The block ids of a chunk, stored as a flat array of indices into a palette of the block
+/// ids that occur in it.
+///
+/// Chunks only hold a handful of block types, so each block takes two bytes instead of a
+/// full id, and reads and writes stay O(1). Blocks are laid out in `[x][y][z]` order.
+/// Each palette entry counts the blocks that use it, and is removed as soon as its last
+/// block is replaced, so a chunk of a single block type has a single-entry palette.
+#[derive(Clone, Debug)]
+pub struct ChunkData {
+    palette: Vec<u32>,
+    /// The number of blocks using each palette entry.
+    counts: Vec<u32>,
+    indices: Vec<u16>,
+}
+
+impl ChunkData {
+    /// Creates a new `ChunkData` filled with air.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChunkData` instance with a single-entry palette.
+    pub fn new() -> Self {
+        ChunkData {
+            palette: vec![0],
+            counts: vec![CHUNK_VOLUME as u32],
+            indices: vec![0; CHUNK_VOLUME],
+        }
+    }
+
+    /// Returns the block id at the given chunk-local coordinates.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.palette[self.indices[Self::index(x, y, z)] as usize]
+    }
+
+    /// Sets the block id at the given chunk-local coordinates, adding it to the palette if
+    /// it isn't in it yet and removing the replaced id once no block uses it anymore.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, block_id: u32) {
+        let index = Self::index(x, y, z);
+        let old_entry = self.indices[index] as usize;
+        if self.palette[old_entry] == block_id {
+            return;
+        }
+
+        let entry = match self.palette.iter().position(|&id| id == block_id) {
+            Some(entry) => entry,
+            None => {
+                self.palette.push(block_id);
+                self.counts.push(0);
+                self.palette.len() - 1
+            }
+        };
+        self.indices[index] = entry as u16;
+        self.counts[entry] += 1;
+
+        self.counts[old_entry] -= 1;
+        if self.counts[old_entry] == 0 {
+            self.remove_entry(old_entry);
+        }
+    }
+
+    /// Returns the block ids of the palette, each used by at least one block.
+    pub fn palette(&self) -> &[u32] {
+        &self.palette
+    }
+
+    /// Returns the memory used by the palette and the block indices, in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.palette.capacity() * size_of::<u32>()
+            + self.counts.capacity() * size_of::<u32>()
+            + self.indices.capacity() * size_of::<u16>()
+    }
+
+    /// Removes a palette entry no block uses anymore.
+    ///
+    /// The last entry takes its place, so only the blocks using the last entry are
+    /// renumbered.
+    fn remove_entry(&mut self, entry: usize) {
+        let last = self.palette.len() - 1;
+        self.palette.swap_remove(entry);
+        self.counts.swap_remove(entry);
+        if entry != last {
+            for index in self.indices.iter_mut() {
+                if *index as usize == last {
+                    *index = entry as u16;
+                }
+            }
+        }
+    }
+
+    /// Returns the position of a block in the index array.
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        (x * CHUNK_HEIGHT + y) * CHUNK_SIZE + z
+    }
+}
+
+/// The block light levels of a chunk, indexed as `[x][y][z]` like `ChunkData`.
+pub type LightData = [[[u8; CHUNK_SIZE]; CHUNK_HEIGHT]; CHUNK_SIZE];
+
+/// Statistics about the last mesh built for a chunk, for profiling.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChunkMeshStats {
+    /// The number of vertices in the mesh.
+    pub vertex_count: usize,
+    /// The number of triangles in the mesh.
+    pub triangle_count: usize,
+    /// How long building the mesh vertices took.
+    pub build_time: Duration,
+    /// The decaying count of recent mesh builds, as of `built_at`.
+    pub rebuild_heat: f32,
+    /// When the mesh was last built.
+    pub built_at: Option<Instant>,
+}
+
+/// How long the rebuild heat of a chunk takes to halve, in seconds.
+const HEAT_HALF_LIFE: f32 = 2.0;
+
+impl ChunkMeshStats {
+    /// Returns how often the chunk was re-meshed recently.
+    ///
+    /// Every build adds one, and the count halves every `HEAT_HALF_LIFE` seconds, so a
+    /// chunk rebuilt every frame stays hot while an edited one cools down quickly.
+    pub fn heat(&self) -> f32 {
+        match self.built_at {
+            Some(built_at) => {
+                let half_lives = built_at.elapsed().as_secs_f32() / HEAT_HALF_LIFE;
+                self.rebuild_heat * 0.5f32.powf(half_lives)
+            }
+            None => 0.0,
+        }
+    }
+}
+
+/// Represents a column of blocks in the world along with its render mesh.
+pub struct Chunk {
+    pub blocks: ChunkData,
+    /// The block light levels, kept up to date by the `lighting` module.
+    pub light: Box<LightData>,
+    pub mesh: Option<ChunkMesh>,
+    /// The faces of translucent blocks such as glass, drawn after all opaque meshes.
+    pub translucent_mesh: Option<ChunkMesh>,
+    pub mesh_stats: ChunkMeshStats,
+    /// Set when the blocks were edited since the chunk was last saved.
+    pub dirty: bool,
+    /// Incremented on every edit, so a save can tell whether the chunk changed mid-write.
+    pub version: u64,
+}
+
+impl Chunk {
+    /// Creates a new `Chunk` from generated block data. The chunk starts without a mesh,
+    /// which is built once it is queued in `World::mesh_updates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The block ids of the chunk.
+    ///
+    /// # Returns
+    ///
+    /// A new `Chunk` instance.
+    pub fn new(blocks: ChunkData) -> Self {
+        Chunk {
+            blocks,
+            light: Box::new([[[0; CHUNK_SIZE]; CHUNK_HEIGHT]; CHUNK_SIZE]),
+            mesh: None,
+            translucent_mesh: None,
+            mesh_stats: ChunkMeshStats::default(),
+            dirty: false,
+            version: 0,
+        }
+    }
+
+    /// Returns the block id at the given chunk-local coordinates.
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.blocks.get(x, y, z)
+    }
+
+    /// Sets the block id at the given chunk-local coordinates and flags the chunk for saving.
+    ///
+    /// Re-meshing is queued by `World::set_block`.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block_id: u32) {
+        self.blocks.set(x, y, z, block_id);
+        self.dirty = true;
+        self.version += 1;
+    }
+}
+
+/// Returns the chunk coordinates containing the given world block column.
+///
+/// # Arguments
+///
+/// * `x` - The world block x coordinate.
+/// * `z` - The world block z coordinate.
+///
+/// # Returns
+///
+/// The `(chunk_x, chunk_z)` coordinates of the chunk.
+pub fn chunk_coords(x: i32, z: i32) -> (i32, i32) {
+    (
+        split_coordinate(x, CHUNK_SIZE).0,
+        split_coordinate(z, CHUNK_SIZE).0,
+    )
+}
+
+/// Returns the chunk-local coordinates of the given world block column.
+///
+/// # Arguments
+///
+/// * `x` - The world block x coordinate.
+/// * `z` - The world block z coordinate.
+///
+/// # Returns
+///
+/// The `(x, z)` coordinates inside the containing chunk.
+pub fn local_coords(x: i32, z: i32) -> (usize, usize) {
+    (
+        split_coordinate(x, CHUNK_SIZE).1,
+        split_coordinate(z, CHUNK_SIZE).1,
+    )
+}
+
+/// Splits a world block coordinate into the coordinate of its chunk and the coordinate
+/// inside that chunk, for chunks `size` blocks wide.
+///
+/// Negative coordinates round toward negative infinity, so the local coordinate is always
+/// in `0..size`. Power of two sizes use a shift and a mask instead of a division; with a
+/// constant size the check is resolved at compile time.
+fn split_coordinate(value: i32, size: usize) -> (i32, usize) {
+    if size.is_power_of_two() {
+        // An arithmetic shift rounds toward negative infinity, like `div_euclid`, and
+        // masking a two's complement value keeps it positive, like `rem_euclid`
+        let shift = size.trailing_zeros();
+        return (value >> shift, (value & (size as i32 - 1)) as usize);
+    }
+    (
+        value.div_euclid(size as i32),
+        value.rem_euclid(size as i32) as usize,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_split_for_power_of_two_size() {
+        assert_eq!(split_coordinate(0, 16), (0, 0));
+        assert_eq!(split_coordinate(15, 16), (0, 15));
+        assert_eq!(split_coordinate(16, 16), (1, 0));
+        assert_eq!(split_coordinate(-1, 16), (-1, 15));
+        assert_eq!(split_coordinate(-16, 16), (-1, 0));
+        assert_eq!(split_coordinate(-17, 16), (-2, 15));
+    }
+
+    #[test]
+    fn coordinates_split_for_other_size() {
+        assert_eq!(split_coordinate(0, 17), (0, 0));
+        assert_eq!(split_coordinate(16, 17), (0, 16));
+        assert_eq!(split_coordinate(17, 17), (1, 0));
+        assert_eq!(split_coordinate(-1, 17), (-1, 16));
+        assert_eq!(split_coordinate(-17, 17), (-1, 0));
+        assert_eq!(split_coordinate(-18, 17), (-2, 16));
+    }
+
+    #[test]
+    fn uniform_chunk_has_a_single_palette_entry() {
+        let mut blocks = ChunkData::new();
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_HEIGHT {
+                for z in 0..CHUNK_SIZE {
+                    blocks.set(x, y, z, 3);
+                }
+            }
+        }
+
+        assert_eq!(blocks.palette(), [3]);
+        assert_eq!(blocks.get(7, 9, 11), 3);
+    }
+
+    #[test]
+    fn removing_palette_entries_keeps_the_block_ids() {
+        let mut blocks = ChunkData::new();
+        let mut expected = vec![0; CHUNK_VOLUME];
+        let mut set = |blocks: &mut ChunkData, [x, y, z]: [usize; 3], id: u32| {
+            blocks.set(x, y, z, id);
+            expected[ChunkData::index(x, y, z)] = id;
+        };
+
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                set(&mut blocks, [x, 0, z], 3);
+                set(&mut blocks, [x, 1, z], (x + z) as u32 % 5 + 1);
+            }
+        }
+        // Replace the only blocks of some ids, including entries in the middle of the
+        // palette, so the entries after them are renumbered
+        for id in [40, 41, 42, 20] {
+            set(&mut blocks, [2, 5, 2], id);
+        }
+        set(&mut blocks, [2, 1, 2], 43);
+        set(&mut blocks, [2, 1, 2], 1);
+
+        let actual: Vec<u32> = (0..CHUNK_VOLUME)
+            .map(|i| blocks.palette[blocks.indices[i] as usize])
+            .collect();
+        assert_eq!(actual, expected);
+        assert_eq!(blocks.palette().len(), 7);
+        for id in [40, 41, 42, 43] {
+            assert!(!blocks.palette().contains(&id));
+        }
+    }
+}
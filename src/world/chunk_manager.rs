@@ -0,0 +1,405 @@
+use crate::config::{Config, GraphicsConfig, LoadOrder};
+use crate::rendering::chunk_mesh::{ChunkMesh, VERTEX_STRIDE};
+use crate::world::border::WorldBorder;
+use crate::world::chunk::{Chunk, ChunkMeshStats, chunk_coords};
+use crate::world::diagnostics::WorldDiagnostics;
+use crate::world::generation::{Terrain, generate_chunk};
+use crate::world::lighting::{light_chunk, update_changed_blocks};
+use crate::world::meshing::{ChunkVertices, build_chunk_vertices};
+use crate::world::save::load_chunk;
+use crate::world::world::World;
+use cgmath::{InnerSpace, Point3, Vector2};
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// The horizontal speed below which chunks are loaded nearest first, in blocks per second.
+const MIN_PREFETCH_SPEED: f32 = 0.5;
+
+/// The share of the per-frame chunk budget that loading chunks may use. The rest is left
+/// for relighting and meshing, so they keep up while new terrain is loaded.
+const LOAD_BUDGET_SHARE: f32 = 0.5;
+
+/// Loads, unloads and meshes chunks around the player.
+///
+/// Chunk generation, lighting and mesh uploads are spread across frames: each call to
+/// `update` stops processing once the configured per-frame time budget or mesh upload limit
+/// is used up and resumes on the next frame, so moving into new terrain doesn't cause frame
+/// hitches. Each pass handles at least one chunk per frame, so none of them stalls while
+/// another has work queued.
+pub struct ChunkManager {
+    load_queue: VecDeque<(i32, i32)>,
+    center: Option<(i32, i32)>,
+    render_distance: i32,
+    /// Whether the chunk meshes were built with ambient occlusion.
+    ambient_occlusion: bool,
+    /// The resources used by the loaded chunks, measured after every update.
+    pub diagnostics: WorldDiagnostics,
+    since_last_log: f32,
+    /// The player position in block space on the previous update.
+    last_position: Option<Point3<f32>>,
+    /// The horizontal velocity of the player in block space, in blocks per second.
+    velocity: Vector2<f32>,
+}
+
+impl ChunkManager {
+    /// Creates a new `ChunkManager` with an empty load queue.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChunkManager` instance.
+    pub fn new() -> Self {
+        ChunkManager {
+            load_queue: VecDeque::new(),
+            center: None,
+            render_distance: 0,
+            ambient_occlusion: false,
+            diagnostics: WorldDiagnostics::default(),
+            since_last_log: 0.0,
+            last_position: None,
+            velocity: Vector2::new(0.0, 0.0),
+        }
+    }
+
+    /// Updates the loaded chunks around the player, within the per-frame time budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A mutable reference to the game world.
+    /// * `player_position` - The current position of the player.
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        player_position: Point3<f32>,
+        delta_time: f32,
+        config: &Config,
+    ) {
+        let player_block = world.to_block_space(player_position);
+        if let Some(last) = self.last_position
+            && delta_time > 0.0
+        {
+            self.velocity =
+                Vector2::new(player_block.x - last.x, player_block.z - last.z) / delta_time;
+        }
+        self.last_position = Some(player_block);
+
+        self.update_chunks(world, player_position, config);
+        self.diagnostics = WorldDiagnostics::measure(world);
+        self.diagnostics.pending_loads = self.load_queue.len();
+
+        // Print the diagnostics periodically, so they can be followed over a session
+        let interval = config.ui.diagnostics_interval;
+        self.since_last_log += delta_time;
+        if interval > 0.0 && self.since_last_log >= interval {
+            self.since_last_log = 0.0;
+            println!(
+                "chunks={} vertices={} memory_mib={:.1}",
+                self.diagnostics.loaded_chunks,
+                self.diagnostics.mesh_vertices,
+                self.diagnostics.total_memory_mib()
+            );
+        }
+    }
+
+    /// Loads, unloads and meshes chunks until the per-frame time budget is used up.
+    fn update_chunks(&mut self, world: &mut World, player_position: Point3<f32>, config: &Config) {
+        let built = self.build_chunks(world, player_position, config);
+
+        // Upload the new meshes; this needs the GL context, so it is kept apart from the
+        // chunk work above
+        for (coord, vertices, build_time) in built {
+            if let Some(chunk) = world.chunks.get_mut(&coord) {
+                let vertex_count =
+                    (vertices.opaque.len() + vertices.translucent.len()) / VERTEX_STRIDE;
+                chunk.mesh_stats = ChunkMeshStats {
+                    vertex_count,
+                    triangle_count: vertex_count / 3,
+                    build_time,
+                    rebuild_heat: chunk.mesh_stats.heat() + 1.0,
+                    built_at: Some(Instant::now()),
+                };
+                chunk.mesh = Some(ChunkMesh::new(&vertices.opaque));
+                chunk.translucent_mesh = (!vertices.translucent.is_empty())
+                    .then(|| ChunkMesh::new(&vertices.translucent));
+            }
+        }
+    }
+
+    /// Loads and relights chunks and builds the vertices of the chunks waiting for a mesh,
+    /// within the per-frame time budget.
+    ///
+    /// Loading only gets part of the budget, and the light and mesh passes always make
+    /// progress, so loaded chunks and player edits are still meshed while the player walks
+    /// into new terrain.
+    ///
+    /// # Returns
+    ///
+    /// The coordinates, vertices and build time of each chunk to upload a mesh for.
+    fn build_chunks(
+        &mut self,
+        world: &mut World,
+        player_position: Point3<f32>,
+        config: &Config,
+    ) -> Vec<((i32, i32), ChunkVertices, Duration)> {
+        let start = Instant::now();
+        let budget = Duration::from_secs_f32(config.world.chunk_budget_ms.max(0.0) / 1000.0);
+
+        let player_block = world.to_block_space(player_position);
+        let center = chunk_coords(player_block.x.floor() as i32, player_block.z.floor() as i32);
+        // Reload when the player enters a new chunk or the render distance changes
+        if self.center != Some(center) || self.render_distance != config.world.render_distance {
+            self.center = Some(center);
+            self.render_distance = config.world.render_distance;
+            Self::unload_distant_chunks(world, center, config.world.render_distance);
+            self.refresh_load_queue(world, center, config);
+        }
+
+        // Re-mesh every chunk when ambient occlusion is switched, such as by a quality preset
+        if self.ambient_occlusion != config.graphics.ambient_occlusion {
+            self.ambient_occlusion = config.graphics.ambient_occlusion;
+            let loaded: Vec<(i32, i32)> = world.chunks.keys().copied().collect();
+            for coord in loaded {
+                world.queue_mesh(coord);
+            }
+        }
+
+        // Generate queued chunks, nearest (and ahead of the player) first
+        while let Some(coord) = self.load_queue.pop_front() {
+            if world.chunks.contains_key(&coord) {
+                continue;
+            }
+
+            Self::load_or_generate(world, coord, config);
+
+            if start.elapsed() >= budget.mul_f32(LOAD_BUDGET_SHARE) {
+                break;
+            }
+        }
+
+        // Update the light around edited blocks first, so the meshes below use the new
+        // light. This only touches the blocks the light reaches, so it is never put off
+        update_changed_blocks(world);
+
+        // Light newly loaded chunks. Chunks left over when the budget runs out are lit on
+        // the next frame
+        let mut light_updates: Vec<(i32, i32)> = world.light_updates.iter().copied().collect();
+        Self::sort_updates(&mut light_updates, center, config);
+        for coord in light_updates {
+            world.light_updates.remove(&coord);
+            light_chunk(world, coord);
+
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+
+        // Build meshes for chunks whose blocks changed, nearest first. Chunks that were
+        // unloaded since they were queued are dropped
+        world
+            .mesh_updates
+            .retain(|coord| world.chunks.contains_key(coord));
+        let mut pending: Vec<(i32, i32)> = world.mesh_updates.iter().copied().collect();
+        Self::sort_updates(&mut pending, center, config);
+
+        // Uploading many meshes at once stalls the GPU, so the rest wait for the next frame
+        let max_uploads = match config.world.max_mesh_uploads_per_frame {
+            0 => usize::MAX,
+            limit => limit,
+        };
+        let mut built = Vec::new();
+        for coord in pending.into_iter().take(max_uploads) {
+            world.mesh_updates.remove(&coord);
+            let build_start = Instant::now();
+            let vertices = Self::build_vertices_safely(world, coord, &config.graphics);
+            built.push((coord, vertices, build_start.elapsed()));
+
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+        built
+    }
+
+    /// Loads a chunk from its save, or generates it if it was never saved.
+    ///
+    /// The chunk is queued for lighting and its neighbors are flagged for re-meshing.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A mutable reference to the game world.
+    /// * `coord` - The coordinates of the chunk.
+    /// * `config` - A reference to the game configuration.
+    pub fn load_or_generate(world: &mut World, coord: (i32, i32), config: &Config) {
+        // Prefer the saved chunk so edits survive unloading
+        let terrain = Terrain::new(world.seed, &config.world);
+        let blocks = load_chunk(&world.save_directory, coord, terrain)
+            .unwrap_or_else(|| generate_chunk(coord.0, coord.1, terrain));
+        world.chunks.insert(coord, Chunk::new(blocks));
+        world.light_updates.insert(coord);
+        world.mesh_updates.insert(coord);
+        Self::mark_neighbors_for_meshing(world, coord);
+    }
+
+    /// Builds the vertices of a chunk, falling back to an empty mesh if meshing fails.
+    ///
+    /// A panic during meshing or invalid vertex data (such as NaN positions from corrupt
+    /// blocks) is logged with the chunk coordinates instead of crashing the game, so the
+    /// rest of the world keeps working. The chunk is re-meshed after its next edit.
+    fn build_vertices_safely(
+        world: &World,
+        coord: (i32, i32),
+        graphics: &GraphicsConfig,
+    ) -> ChunkVertices {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            build_chunk_vertices(
+                world,
+                coord.0,
+                coord.1,
+                graphics.cull_world_bottom,
+                graphics.ambient_occlusion,
+            )
+        }));
+
+        let valid = |vertices: &[f32]| {
+            vertices.len().is_multiple_of(VERTEX_STRIDE)
+                && vertices.iter().all(|value| value.is_finite())
+        };
+        match result {
+            Ok(vertices) if valid(&vertices.opaque) && valid(&vertices.translucent) => vertices,
+            Ok(_) => {
+                eprintln!(
+                    "Chunk {:?} produced invalid mesh data; using an empty mesh",
+                    coord
+                );
+                ChunkVertices::default()
+            }
+            Err(_) => {
+                eprintln!("Meshing chunk {:?} panicked; using an empty mesh", coord);
+                ChunkVertices::default()
+            }
+        }
+    }
+
+    /// Unloads chunks that are farther from the player than the render distance allows.
+    ///
+    /// Chunks with unsaved edits stay loaded until the `WorldSaver` has written them.
+    fn unload_distant_chunks(world: &mut World, center: (i32, i32), render_distance: i32) {
+        // Keep one extra ring loaded so chunks don't thrash when walking along a border
+        world.chunks.retain(|&coord, chunk| {
+            chunk.dirty || Self::distance(coord, center) <= render_distance + 1
+        });
+    }
+
+    /// Rebuilds the load queue with all missing chunks in range, ordered nearest first.
+    /// Chunks outside the world border are left out.
+    ///
+    /// While the player moves, chunks in the direction of travel are moved forward in the
+    /// queue by up to `prefetch_bias` chunks, and chunks behind are moved back, so the view
+    /// ahead stays populated during fast travel. The `sorted` load order ignores both and
+    /// orders the chunks by coordinate.
+    fn refresh_load_queue(&mut self, world: &World, center: (i32, i32), config: &Config) {
+        let render_distance = config.world.render_distance;
+        let border = WorldBorder::new(&config.world);
+        let mut missing = Vec::new();
+        for chunk_x in (center.0 - render_distance)..=(center.0 + render_distance) {
+            for chunk_z in (center.1 - render_distance)..=(center.1 + render_distance) {
+                let inside = border.is_none_or(|border| border.contains_chunk((chunk_x, chunk_z)));
+                if inside && !world.chunks.contains_key(&(chunk_x, chunk_z)) {
+                    missing.push((chunk_x, chunk_z));
+                }
+            }
+        }
+
+        if config.world.load_order == LoadOrder::Sorted {
+            missing.sort();
+            self.load_queue = missing.into();
+            return;
+        }
+
+        let bias = config.world.prefetch_bias;
+        let heading = (bias != 0.0 && self.velocity.magnitude() >= MIN_PREFETCH_SPEED)
+            .then(|| self.velocity.normalize());
+        let priority = |coord: (i32, i32)| {
+            let distance = Self::distance(coord, center) as f32;
+            let offset = Vector2::new((coord.0 - center.0) as f32, (coord.1 - center.1) as f32);
+            match heading {
+                Some(heading) if offset.magnitude2() > 0.0 => {
+                    distance - bias * offset.normalize().dot(heading)
+                }
+                _ => distance,
+            }
+        };
+        missing.sort_by(|&a, &b| priority(a).total_cmp(&priority(b)));
+
+        self.load_queue = missing.into();
+    }
+
+    /// Sorts chunks waiting for lighting or meshing, nearest first or by coordinate.
+    ///
+    /// The updates are collected from a hash set, so ties are broken by coordinate to keep
+    /// the order the same from run to run.
+    fn sort_updates(updates: &mut [(i32, i32)], center: (i32, i32), config: &Config) {
+        match config.world.load_order {
+            LoadOrder::Nearest => {
+                updates.sort_by_key(|&coord| (Self::distance(coord, center), coord))
+            }
+            LoadOrder::Sorted => updates.sort(),
+        }
+    }
+
+    /// Flags the four horizontal neighbors of a chunk for re-meshing, so their border faces
+    /// are culled against the newly loaded blocks.
+    fn mark_neighbors_for_meshing(world: &mut World, coord: (i32, i32)) {
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            world.queue_mesh((coord.0 + dx, coord.1 + dz));
+        }
+    }
+
+    /// Returns the distance between two chunks, in chunks (Chebyshev distance).
+    fn distance(a: (i32, i32), b: (i32, i32)) -> i32 {
+        (a.0 - b.0).abs().max((a.1 - b.1).abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrupt_blocks_mesh_without_invalid_vertices() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        // Ids past the last known block, as read from a damaged save
+        world.set_block(3, 4, 5, 999);
+        world.set_block(3, 5, 5, u32::MAX);
+        world.set_block(0, 0, 0, u32::MAX - 1);
+
+        let vertices =
+            ChunkManager::build_vertices_safely(&world, (0, 0), &GraphicsConfig::default());
+
+        assert!(!vertices.opaque.is_empty());
+        for values in [&vertices.opaque, &vertices.translucent] {
+            assert!(values.len().is_multiple_of(VERTEX_STRIDE));
+            assert!(values.iter().all(|value| value.is_finite()));
+        }
+    }
+
+    #[test]
+    fn edits_are_meshed_while_loads_are_pending() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.set_block(3, 4, 5, 3);
+        let mut config = Config::default();
+        config.world.block_size = 1.0;
+        config.world.render_distance = 3;
+        // No time budget, so every pass stops after its first chunk
+        config.world.chunk_budget_ms = 0.0;
+
+        let mut manager = ChunkManager::new();
+        let built = manager.build_chunks(&mut world, Point3::new(8.0, 20.0, 8.0), &config);
+
+        assert!(!manager.load_queue.is_empty());
+        assert!(built.iter().any(|(coord, _, _)| *coord == (0, 0)));
+        assert!(!world.mesh_updates.contains(&(0, 0)));
+    }
+}
@@ -6,77 +6,32 @@ use gl::types::*;
 use crate::rendering::mesh::Mesh;
 use crate::rendering::shader::Shader;
 use crate::world::block::Block;
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE, Chunk};
 
-/// Draws a chunk of blocks using the given mesh and shader.
+/// Draw the edges of the blocks in a chunk
 ///
 /// # Arguments
 ///
-/// * `chunk_data` - A 3D array representing the chunk data.
+/// * `chunk` - The chunk whose blocks should be outlined.
+/// * `origin` - The position of the chunk's minimum corner, in blocks.
+/// * `block_size` - The edge length of a block in world units.
 /// * `mesh` - The mesh to use for rendering.
 /// * `shader` - The shader to use for rendering.
-/// * `time` - The current time (used for animations).
-pub fn draw_chunk(chunk_data: &[[[u32; 16]; 16]; 16], mesh: &Mesh, shader: &Shader, _time: f32) {
-    // First pass: Draw solid blocks
-    draw_solid_blocks(chunk_data, mesh, shader);
-
-    // Second pass: Draw edges
-    draw_block_edges(chunk_data, mesh, shader);
-}
-
-/// Draw the solid blocks
+/// * `budget` - The maximum number of blocks to outline.
 ///
-/// # Arguments
+/// # Returns
 ///
-/// * `chunk_data` - A 3D array representing the chunk data.
-/// * `mesh` - The mesh to use for rendering.
-/// * `shader` - The shader to use for rendering.
-fn draw_solid_blocks(chunk_data: &[[[u32; 16]; 16]; 16], mesh: &Mesh, shader: &Shader) {
-    unsafe {
-        for x in 0..16 {
-            for y in 0..16 {
-                for z in 0..16 {
-                    let block_id = chunk_data[x][y][z];
-                    if block_id != 0 {
-                        // Model matrix - use consistent cube size
-                        let model_location =
-                            gl::GetUniformLocation(shader.id, b"model\0".as_ptr() as *const GLchar);
-
-                        // Position cubes with exact 1.0 unit size for perfect cubes
-                        // Use pure translation without scaling to maintain cube proportions
-                        let model: Matrix4<f32> =
-                            Matrix4::from_translation(Vector3::new(x as f32, y as f32, z as f32));
-
-                        gl::UniformMatrix4fv(
-                            model_location,
-                            1,
-                            gl::FALSE as GLboolean,
-                            model.as_ptr(),
-                        );
-
-                        // Apply color based on block ID
-                        let block = Block::new(block_id);
-                        let color = block.get_color();
-                        let color_location =
-                            gl::GetUniformLocation(shader.id, b"color\0".as_ptr() as *const GLchar);
-                        gl::Uniform4f(color_location, color[0], color[1], color[2], color[3]);
-
-                        // Draw the cube
-                        mesh.draw();
-                    }
-                }
-            }
-        }
-    }
-}
+/// The number of blocks that were outlined.
+pub fn draw_block_edges(
+    chunk: &Chunk,
+    origin: Vector3<f32>,
+    block_size: f32,
+    mesh: &Mesh,
+    shader: &Shader,
+    budget: usize,
+) -> usize {
+    let mut outlined = 0;
 
-/// Draw the edges of blocks
-///
-/// # Arguments
-///
-/// * `chunk_data` - A 3D array representing the chunk data.
-/// * `mesh` - The mesh to use for rendering.
-/// * `shader` - The shader to use for rendering.
-fn draw_block_edges(chunk_data: &[[[u32; 16]; 16]; 16], mesh: &Mesh, shader: &Shader) {
     unsafe {
         // Save the current polygon mode
         let mut polygon_mode = [0];
@@ -93,22 +48,38 @@ fn draw_block_edges(chunk_data: &[[[u32; 16]; 16]; 16], mesh: &Mesh, shader: &Sh
         gl::PolygonOffset(-1.0, -1.0);
 
         // Set edge color (black)
-        let color_location =
-            gl::GetUniformLocation(shader.id, b"color\0".as_ptr() as *const GLchar);
+        let color_location = gl::GetUniformLocation(shader.id, c"color".as_ptr());
         gl::Uniform4f(color_location, 0.0, 0.0, 0.0, 1.0);
 
-        for x in 0..16 {
-            for y in 0..16 {
-                for z in 0..16 {
-                    let block_id = chunk_data[x][y][z];
-                    if block_id != 0 {
-                        // Model matrix
-                        let model_location =
-                            gl::GetUniformLocation(shader.id, b"model\0".as_ptr() as *const GLchar);
+        // Model matrix
+        let model_location = gl::GetUniformLocation(shader.id, c"model".as_ptr());
 
-                        // Create basic model matrix for position - use same size as solid cubes
-                        let model =
-                            Matrix4::from_translation(Vector3::new(x as f32, y as f32, z as f32));
+        'blocks: for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_HEIGHT {
+                for z in 0..CHUNK_SIZE {
+                    if !is_exposed(chunk, x, y, z) {
+                        continue;
+                    }
+                    if outlined >= budget {
+                        break 'blocks;
+                    }
+                    outlined += 1;
+
+                    // Outline each box of the block's shape; the cube mesh is centered on the
+                    // origin, while blocks span [x, x + 1]
+                    for shape in Block::new(chunk.get_block(x, y, z)).collision_boxes() {
+                        let center = shape.center();
+                        let size = shape.size();
+                        let model = Matrix4::from_scale(block_size)
+                            * Matrix4::from_translation(
+                                origin
+                                    + Vector3::new(
+                                        x as f32 + center[0],
+                                        y as f32 + center[1],
+                                        z as f32 + center[2],
+                                    ),
+                            )
+                            * Matrix4::from_nonuniform_scale(size[0], size[1], size[2]);
 
                         gl::UniformMatrix4fv(
                             model_location,
@@ -130,4 +101,35 @@ fn draw_block_edges(chunk_data: &[[[u32; 16]; 16]; 16], mesh: &Mesh, shader: &Sh
         // Restore original polygon mode
         gl::PolygonMode(gl::FRONT_AND_BACK, polygon_mode[0] as GLenum);
     }
+
+    outlined
+}
+
+/// Checks whether a block is solid and has at least one neighbor inside its chunk that
+/// doesn't fully cover it.
+///
+/// Blocks on the chunk border are treated as exposed.
+fn is_exposed(chunk: &Chunk, x: usize, y: usize, z: usize) -> bool {
+    if chunk.get_block(x, y, z) == 0 {
+        return false;
+    }
+
+    if x == 0
+        || y == 0
+        || z == 0
+        || x == CHUNK_SIZE - 1
+        || y == CHUNK_HEIGHT - 1
+        || z == CHUNK_SIZE - 1
+    {
+        return true;
+    }
+
+    let block = Block::new(chunk.get_block(x, y, z));
+    let open = |x, y, z| !block.is_face_hidden_by(&Block::new(chunk.get_block(x, y, z)));
+    open(x - 1, y, z)
+        || open(x + 1, y, z)
+        || open(x, y - 1, z)
+        || open(x, y + 1, z)
+        || open(x, y, z - 1)
+        || open(x, y, z + 1)
 }
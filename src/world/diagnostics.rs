@@ -0,0 +1,129 @@
+use crate::config::Config;
+use crate::rendering::chunk_mesh::VERTEX_STRIDE;
+use crate::world::chunk::LightData;
+use crate::world::world::World;
+
+/// Measurements of the resources used by the loaded world, for the debug overlay and logs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WorldDiagnostics {
+    /// The number of loaded chunks.
+    pub loaded_chunks: usize,
+    /// The total number of vertices in all chunk meshes.
+    pub mesh_vertices: usize,
+    /// The estimated memory used by block and light data, in bytes.
+    pub block_memory: usize,
+    /// The estimated GPU memory used by chunk meshes, in bytes.
+    pub mesh_memory: usize,
+    /// The number of chunks waiting for their mesh to be built and uploaded.
+    pub pending_meshes: usize,
+    /// The number of chunks waiting for their light to be recomputed.
+    pub pending_light: usize,
+    /// The number of chunks waiting to be loaded or generated; set by the chunk manager.
+    pub pending_loads: usize,
+}
+
+/// How many slow frames in a row make the frame watchdog log a warning.
+const SLOW_FRAME_STREAK: u32 = 5;
+
+impl WorldDiagnostics {
+    /// Measures the resources used by the loaded chunks.
+    ///
+    /// The memory figures are estimates from the sizes of the chunk arrays and vertex buffers;
+    /// allocator and driver overhead isn't included.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A reference to the game world.
+    ///
+    /// # Returns
+    ///
+    /// The current measurements.
+    pub fn measure(world: &World) -> Self {
+        let loaded_chunks = world.chunks.len();
+        let mesh_vertices = world
+            .chunks
+            .values()
+            .map(|chunk| chunk.mesh_stats.vertex_count)
+            .sum();
+        let block_memory: usize = world
+            .chunks
+            .values()
+            .map(|chunk| chunk.blocks.memory_bytes() + size_of::<LightData>())
+            .sum();
+        let pending_meshes = world.mesh_updates.len();
+        let pending_light = world.light_updates.len();
+
+        WorldDiagnostics {
+            loaded_chunks,
+            mesh_vertices,
+            block_memory,
+            mesh_memory: mesh_vertices * VERTEX_STRIDE * size_of::<f32>(),
+            pending_meshes,
+            pending_light,
+            pending_loads: 0,
+        }
+    }
+
+    /// Returns the total estimated memory, in mebibytes.
+    pub fn total_memory_mib(&self) -> f32 {
+        (self.block_memory + self.mesh_memory) as f32 / (1024.0 * 1024.0)
+    }
+}
+
+/// Logs what the world was busy with when several frames in a row take too long.
+///
+/// Only enabled with `ui.frame_watchdog`. The game keeps running; the warning lists the
+/// loaded chunks and the lengths of the work queues, to point at what caused the hitch.
+pub struct FrameWatchdog {
+    slow_frames: u32,
+}
+
+impl FrameWatchdog {
+    /// Creates a new `FrameWatchdog` that hasn't seen a slow frame.
+    ///
+    /// # Returns
+    ///
+    /// A new `FrameWatchdog` instance.
+    pub fn new() -> Self {
+        FrameWatchdog { slow_frames: 0 }
+    }
+
+    /// Counts slow frames, and logs a warning once per streak of `SLOW_FRAME_STREAK` of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - The time the last frame took, in seconds.
+    /// * `diagnostics` - The world measurements of the current frame.
+    /// * `ticking_blocks` - The number of blocks waiting to be ticked.
+    /// * `config` - A reference to the game configuration.
+    pub fn check(
+        &mut self,
+        delta_time: f32,
+        diagnostics: &WorldDiagnostics,
+        ticking_blocks: usize,
+        config: &Config,
+    ) {
+        let threshold_ms = config.ui.slow_frame_ms;
+        let frame_ms = delta_time * 1000.0;
+        if !config.ui.frame_watchdog || frame_ms <= threshold_ms {
+            self.slow_frames = 0;
+            return;
+        }
+
+        self.slow_frames += 1;
+        if self.slow_frames == SLOW_FRAME_STREAK {
+            eprintln!(
+                "{} frames in a row took over {:.0} ms (last {:.1} ms): chunks={} \
+                 pending_loads={} pending_light={} pending_meshes={} ticking_blocks={}",
+                SLOW_FRAME_STREAK,
+                threshold_ms,
+                frame_ms,
+                diagnostics.loaded_chunks,
+                diagnostics.pending_loads,
+                diagnostics.pending_light,
+                diagnostics.pending_meshes,
+                ticking_blocks
+            );
+        }
+    }
+}
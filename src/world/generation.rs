@@ -1,46 +1,211 @@
-/// Generates a chunk of blocks.
+use crate::config::WorldConfig;
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE, ChunkData};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The most the noise raises the terrain above its base height, in blocks.
+const MAX_HEIGHT_OFFSET: u32 = 3;
+
+/// The settings that decide the shape of the generated terrain.
+///
+/// Delta saves are stored relative to the generated terrain, so changing these changes
+/// the terrain under existing saves too.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Terrain {
+    /// The seed of the world.
+    pub seed: u32,
+    /// The most neighboring columns may differ in height, in blocks, or `0` for no limit.
+    pub max_height_step: u32,
+}
+
+impl Terrain {
+    /// Reads the terrain settings from the world configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the world, which changes when the world is regenerated.
+    /// * `config` - The world settings.
+    ///
+    /// # Returns
+    ///
+    /// The terrain settings.
+    pub fn new(seed: u32, config: &WorldConfig) -> Self {
+        Terrain {
+            seed,
+            max_height_step: config.max_height_step,
+        }
+    }
+}
+
+/// Picks a new world seed from the system clock, for regenerating the world at runtime.
 ///
 /// # Returns
 ///
-/// A 3D array representing the chunk data.
-pub fn generate_chunk() -> [[[u32; 16]; 16]; 16] {
-    let mut chunk_data = [[[0; 16]; 16]; 16];
+/// A seed that differs from run to run.
+pub fn random_seed() -> u32 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64);
+    // Fold the clock so nearby times give very different seeds
+    let mixed = nanos.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    (mixed >> 32) as u32
+}
+
+/// A small xorshift pseudo-random number generator, for gameplay randomness such as
+/// spreading grass, wandering mobs and particles.
+///
+/// The same seed always gives the same sequence. It isn't used for the terrain, which is
+/// derived from the world seed and block coordinates instead.
+#[derive(Clone, Copy, Debug)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Creates a new `Rng` from a seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The starting state. Zero would only ever produce zero, so it is replaced
+    ///   with a fixed nonzero state.
+    ///
+    /// # Returns
+    ///
+    /// A new `Rng` instance.
+    pub fn new(seed: u32) -> Self {
+        Rng {
+            state: if seed == 0 { 0x2545_F491 } else { seed },
+        }
+    }
 
-    for x in 0..16 {
-        for z in 0..16 {
-            // Generate terrain with hills and valleys
-            let base_height = 4;
+    /// Returns a pseudo-random number in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1 << 24) as f32
+    }
+}
 
-            // Create some height variation using simple noise
-            let h1 = (x as f32 * 0.5).sin() * 1.5;
-            let h2 = (z as f32 * 0.5).cos() * 1.5;
-            let h3 = ((x as f32 * 0.7 + z as f32 * 0.3) * 0.4).sin() * 0.5;
+/// Generates the chunk of blocks at the given chunk coordinates.
+///
+/// # Arguments
+///
+/// * `chunk_x` - The x coordinate of the chunk.
+/// * `chunk_z` - The z coordinate of the chunk.
+/// * `terrain` - The terrain settings of the world.
+///
+/// # Returns
+///
+/// The block ids of the generated chunk.
+pub fn generate_chunk(chunk_x: i32, chunk_z: i32, terrain: Terrain) -> ChunkData {
+    let mut chunk_data = ChunkData::new();
 
-            let height_offset = (h1 + h2 + h3).max(0.0) as usize;
-            let height = base_height + height_offset;
+    for x in 0..CHUNK_SIZE {
+        for z in 0..CHUNK_SIZE {
+            // Sample the terrain in world coordinates so neighboring chunks line up
+            let height = height_at(
+                chunk_x * CHUNK_SIZE as i32 + x as i32,
+                chunk_z * CHUNK_SIZE as i32 + z as i32,
+                terrain,
+            );
 
             // Generate terrain layers
-            for y in 0..16 {
-                if y < height - 1 {
-                    chunk_data[x][y][z] = 3; // Stone
+            for y in 0..CHUNK_HEIGHT {
+                let block_id = if y < height - 1 {
+                    3 // Stone
                 } else if y < height {
-                    chunk_data[x][y][z] = 1; // Dirt
+                    1 // Dirt
                 } else if y == height {
                     // Add snow on high terrain, grass on lower
                     if height >= 7 {
-                        chunk_data[x][y][z] = 4; // Snow
+                        4 // Snow
                     } else {
-                        chunk_data[x][y][z] = 2; // Grass
+                        2 // Grass
                     }
-                }
-
-                // Add water pools in low areas
-                if y <= 2 && height <= 3 && chunk_data[x][y][z] == 0 {
-                    chunk_data[x][y][z] = 5; // Water
-                }
+                } else if y <= 2 && height <= 3 {
+                    // Add water pools in low areas
+                    5 // Water
+                } else {
+                    continue; // Air
+                };
+                chunk_data.set(x, y, z, block_id);
             }
         }
     }
 
     chunk_data
 }
+
+/// Returns the generated terrain height of a column, ignoring any edits.
+///
+/// The surface block (grass or snow) of the column sits at this height. With a height step
+/// limit, each column is lowered until no neighboring column (including diagonals) is more
+/// than the limit lower, which turns sheer cliffs into slopes.
+///
+/// # Arguments
+///
+/// * `world_x` - The x coordinate of the column, in blocks.
+/// * `world_z` - The z coordinate of the column, in blocks.
+/// * `terrain` - The terrain settings of the world.
+///
+/// # Returns
+///
+/// The y coordinate of the surface block.
+pub fn height_at(world_x: i32, world_z: i32, terrain: Terrain) -> usize {
+    let step = terrain.max_height_step;
+    if step == 0 || step >= MAX_HEIGHT_OFFSET {
+        return noise_height(world_x, world_z, terrain.seed);
+    }
+
+    // The lowest height reachable from any nearby column by climbing at most `step` blocks
+    // per column. Columns further away than this radius can't constrain the height, since
+    // heights only vary by `MAX_HEIGHT_OFFSET`
+    let radius = MAX_HEIGHT_OFFSET.div_ceil(step) as i32;
+    let mut height = usize::MAX;
+    for dx in -radius..=radius {
+        for dz in -radius..=radius {
+            let distance = dx.abs().max(dz.abs()) as usize;
+            let reachable =
+                noise_height(world_x + dx, world_z + dz, terrain.seed) + distance * step as usize;
+            height = height.min(reachable);
+        }
+    }
+    height
+}
+
+/// Returns the terrain height of a column straight from the noise.
+fn noise_height(world_x: i32, world_z: i32, seed: u32) -> usize {
+    // Shift the noise by the seed; seed 0 keeps the original terrain
+    let world_x = world_x as f32 + (seed & 0xFFFF) as f32 * 0.37;
+    let world_z = world_z as f32 + (seed >> 16) as f32 * 0.53;
+
+    // Generate terrain with hills and valleys
+    let base_height = 4;
+
+    // Create some height variation using simple noise
+    let h1 = (world_x * 0.5).sin() * 1.5;
+    let h2 = (world_z * 0.5).cos() * 1.5;
+    let h3 = ((world_x * 0.7 + world_z * 0.3) * 0.4).sin() * 0.5;
+
+    let height_offset = (h1 + h2 + h3).max(0.0) as usize;
+    base_height + height_offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_repeats_its_sequence_within_range() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+        for _ in 0..1000 {
+            let value = first.next_f32();
+            assert!((0.0..1.0).contains(&value));
+            assert_eq!(value, second.next_f32());
+        }
+
+        let mut zero = Rng::new(0);
+        assert!((0..10).any(|_| zero.next_f32() > 0.0));
+    }
+}
@@ -3,23 +3,44 @@ extern crate gl;
 extern crate glfw;
 
 use crate::config::Config;
+use crate::events::apply_cursor_grab;
 use crate::player::camera::Camera;
+use crate::rendering::crosshair::Crosshair;
+use crate::rendering::gizmos::Gizmos;
+use crate::rendering::highlight::BlockHighlight;
 use crate::rendering::mesh::Mesh;
+use crate::rendering::particles::ParticleSystem;
 use crate::rendering::shader::Shader;
-use crate::world::{block::Block, world::World};
-use cgmath::{Deg, Matrix4, Point3, perspective};
-use glfw::{Context, CursorMode, Glfw, GlfwReceiver, PWindow, WindowEvent};
+use crate::rendering::shadow_map::ShadowMap;
+use crate::rendering::text::TextRenderer;
+use crate::world::mobs::Mobs;
+use crate::world::spawn::{spawn_position, surface_position};
+use crate::world::ticking::TickingBlocks;
+use crate::world::{block::Block, chunk_manager::ChunkManager, save::WorldSaver, world::World};
+use cgmath::{Matrix4, Vector3};
+use glfw::{Context, Glfw, GlfwReceiver, PWindow, WindowEvent};
 
-/// Represents the main application state, including window, camera, shader, and world.
+/// Represents the main application state, including window, camera, shaders, and world.
 pub struct App {
     pub glfw: Glfw,
     pub window: PWindow,
     pub events: GlfwReceiver<(f64, WindowEvent)>,
     pub shader: Shader,
+    pub chunk_shader: Shader,
+    pub shadow_map: ShadowMap,
     pub world: World,
+    pub chunk_manager: ChunkManager,
+    pub world_saver: WorldSaver,
+    pub ticking_blocks: TickingBlocks,
+    pub mobs: Mobs,
     pub projection: Matrix4<f32>,
     pub view: Matrix4<f32>,
     pub camera: Camera,
+    pub crosshair: Crosshair,
+    pub gizmos: Gizmos,
+    pub highlight: BlockHighlight,
+    pub particles: ParticleSystem,
+    pub text: TextRenderer,
 }
 
 impl App {
@@ -36,6 +57,11 @@ impl App {
         let mut glfw = glfw::init(glfw::fail_on_errors).expect("Failed to initialize GLFW");
 
         glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
+        if config.graphics.msaa_samples > 0 {
+            glfw.window_hint(glfw::WindowHint::Samples(Some(
+                config.graphics.msaa_samples,
+            )));
+        }
         glfw.window_hint(glfw::WindowHint::OpenGlProfile(
             glfw::OpenGlProfileHint::Core,
         ));
@@ -53,17 +79,17 @@ impl App {
         // Apply fullscreen if configured
         if config.window.fullscreen {
             glfw.with_primary_monitor(|_, m| {
-                if let Some(monitor) = m {
-                    if let Some(video_mode) = monitor.get_video_mode() {
-                        window.set_monitor(
-                            glfw::WindowMode::FullScreen(monitor),
-                            0,
-                            0,
-                            video_mode.width,
-                            video_mode.height,
-                            Some(video_mode.refresh_rate),
-                        );
-                    }
+                if let Some(monitor) = m
+                    && let Some(video_mode) = monitor.get_video_mode()
+                {
+                    window.set_monitor(
+                        glfw::WindowMode::FullScreen(monitor),
+                        0,
+                        0,
+                        video_mode.width,
+                        video_mode.height,
+                        Some(video_mode.refresh_rate),
+                    );
                 }
             });
         }
@@ -72,10 +98,10 @@ impl App {
         window.set_key_polling(true);
         window.set_framebuffer_size_polling(true);
         window.set_cursor_pos_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_scroll_polling(true);
 
-        if config.controls.cursor_locked {
-            window.set_cursor_mode(CursorMode::Disabled);
-        }
+        apply_cursor_grab(&mut window, config.controls.cursor_locked, &config.controls);
 
         gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
 
@@ -83,41 +109,100 @@ impl App {
             "src/shaders/vertex_shader.glsl",
             "src/shaders/fragment_shader.glsl",
         );
+        let chunk_shader = Shader::new(
+            "src/shaders/chunk_vertex_shader.glsl",
+            "src/shaders/chunk_fragment_shader.glsl",
+        );
         let cube_vertices: [f32; 108] = Block::get_cube_vertices();
         let mesh = Mesh::new(&cube_vertices);
-        let world = World::new(mesh);
+        let mut world = World::new(mesh, &config.world);
+        let chunk_manager = ChunkManager::new();
+
+        // Place the camera on the ground at the spawn point
+        let camera = Camera::new(spawn_position(&mut world, config));
 
         // Get the current framebuffer size for projection matrix
         let (width, height) = window.get_framebuffer_size();
-        let projection: Matrix4<f32> = perspective(
-            Deg(config.camera.fov),
-            width as f32 / height as f32,
-            config.camera.near_plane,
-            config.camera.far_plane,
-        );
-
-        // Initialize camera at config position
-        let camera = Camera::new(Point3::new(
-            config.camera.position_x,
-            config.camera.position_y,
-            config.camera.position_z,
-        ));
-        let view = camera.get_view_matrix();
+        let projection: Matrix4<f32> =
+            camera.projection_matrix(width as f32 / height as f32, config);
+        let view = camera.get_view_matrix(Vector3::new(0.0, 0.0, 0.0));
 
         App {
             glfw,
             window,
             events,
             shader,
+            chunk_shader,
+            shadow_map: ShadowMap::new(&config.graphics),
             world,
+            chunk_manager,
+            world_saver: WorldSaver::new(),
+            ticking_blocks: TickingBlocks::new(),
+            mobs: Mobs::new(),
             projection,
             view,
             camera,
+            crosshair: Crosshair::new(),
+            gizmos: Gizmos::new(),
+            highlight: BlockHighlight::new(),
+            particles: ParticleSystem::new(),
+            text: TextRenderer::new(),
         }
     }
 
-    /// Updates the view matrix based on the current camera orientation.
-    pub fn update_view_matrix(&mut self) {
-        self.view = self.camera.get_view_matrix();
+    /// Throws away the loaded world and generates a new one from another seed, around the
+    /// player.
+    ///
+    /// The edits of the old world are saved first. The new world is saved next to it, in a
+    /// directory named after the seed, so the edits of the two worlds never mix. All chunks,
+    /// queued lighting and meshing, ticking blocks, mobs and particles start over, the mirror
+    /// plane is removed, and the player is put on the surface of the column they stand in.
+    /// The caller resets the player's own tools, such as a block being broken.
+    ///
+    /// The seed and save directory only change for this run; the configuration keeps the
+    /// world that is loaded on the next start.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the game configuration.
+    /// * `seed` - The seed of the new world.
+    pub fn regenerate_world(&mut self, config: &Config, seed: u32) {
+        // Wait for the running save and write what was edited since, under the old seed
+        self.world_saver
+            .save_world_blocking(&mut self.world, config);
+
+        self.world.clear();
+        self.world.seed = seed;
+        self.world.save_directory = seed_save_directory(&self.world.save_directory, seed);
+        self.chunk_manager = ChunkManager::new();
+        self.world_saver = WorldSaver::new();
+        self.ticking_blocks = TickingBlocks::new();
+        self.mobs = Mobs::new();
+        self.particles.clear();
+        self.world.mirror = None;
+
+        let feet = self.world.to_block_space(self.camera.feet_position(config));
+        let column = (feet.x.floor() as i32, feet.z.floor() as i32);
+        self.camera.position = surface_position(&mut self.world, column, config);
+        println!(
+            "Regenerated the world with seed {}, saved to {}",
+            seed, self.world.save_directory
+        );
+    }
+
+    /// Updates the view matrix based on the current camera orientation and view offset.
+    pub fn update_view_matrix(&mut self, offset: Vector3<f32>) {
+        self.view = self.camera.get_view_matrix(offset);
     }
 }
+
+/// Returns the save directory of the world generated from a seed at runtime, next to the
+/// configured one: `saves/world` becomes `saves/world-seed-42`.
+fn seed_save_directory(directory: &str, seed: u32) -> String {
+    // Regenerating again replaces the seed instead of stacking suffixes
+    let base = match directory.rsplit_once("-seed-") {
+        Some((base, suffix)) if suffix.parse::<u32>().is_ok() => base,
+        _ => directory,
+    };
+    format!("{}-seed-{}", base, seed)
+}
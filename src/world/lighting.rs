@@ -0,0 +1,373 @@
+use crate::world::block::Block;
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE, chunk_coords, local_coords};
+use crate::world::world::World;
+use std::collections::HashSet;
+
+/// The brightest block light level.
+pub const MAX_LIGHT: u8 = 15;
+
+/// The offsets to the six blocks sharing a face with a block.
+const NEIGHBORS: [[i32; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+/// A block light update in progress.
+///
+/// Light spreads from emitting blocks (such as torches) through every block that isn't a
+/// full cube, losing one level per block. Where the light of several sources overlaps, a
+/// block keeps the brightest level that reaches it rather than adding them up.
+///
+/// Light is updated incrementally: a changed block only floods the blocks its light reached
+/// before or reaches now, and chunks are only re-meshed where the light of a block changed,
+/// including the neighbor across the border when that block is on a chunk border.
+#[derive(Default)]
+struct LightFlood {
+    /// Blocks waiting to spread their light, bucketed by level so the brightest are handled
+    /// first and each block is only spread from once, at its final level.
+    spread: Vec<Vec<[i32; 3]>>,
+    /// Blocks whose light was taken away, with the level they had.
+    removed: Vec<([i32; 3], u8)>,
+    /// Chunks with a block whose light changed, to be re-meshed.
+    changed: HashSet<(i32, i32)>,
+}
+
+impl LightFlood {
+    /// Creates a flood with nothing queued.
+    fn new() -> Self {
+        LightFlood {
+            spread: vec![Vec::new(); MAX_LIGHT as usize + 1],
+            ..LightFlood::default()
+        }
+    }
+
+    /// Sets the light of a block, recording the chunks whose faces it changes.
+    fn set(&mut self, world: &mut World, [x, y, z]: [i32; 3], level: u8) {
+        let (chunk_x, chunk_z) = chunk_coords(x, z);
+        let Some(chunk) = world.chunks.get_mut(&(chunk_x, chunk_z)) else {
+            return;
+        };
+        let (local_x, local_z) = local_coords(x, z);
+        let light = &mut chunk.light[local_x][y as usize][local_z];
+        if *light == level {
+            return;
+        }
+        *light = level;
+
+        // Faces on the border of a chunk are lit by the light in the neighboring chunk
+        self.changed.insert((chunk_x, chunk_z));
+        if local_x == 0 {
+            self.changed.insert((chunk_x - 1, chunk_z));
+        } else if local_x == CHUNK_SIZE - 1 {
+            self.changed.insert((chunk_x + 1, chunk_z));
+        }
+        if local_z == 0 {
+            self.changed.insert((chunk_x, chunk_z - 1));
+        } else if local_z == CHUNK_SIZE - 1 {
+            self.changed.insert((chunk_x, chunk_z + 1));
+        }
+    }
+
+    /// Queues a lit block to spread its light to its neighbors.
+    fn queue_spread(&mut self, position: [i32; 3], level: u8) {
+        if level > 1 {
+            self.spread[level as usize].push(position);
+        }
+    }
+
+    /// Takes the light away from every block that was lit through the removed blocks.
+    ///
+    /// Neighbors dimmer than a removed block got their light from it and are cleared in
+    /// turn. Neighbors at least as bright are lit by another source, so they spread their
+    /// light back into the cleared blocks, as do emitters among the cleared blocks.
+    fn remove(&mut self, world: &mut World) {
+        while let Some((position, level)) = self.removed.pop() {
+            for neighbor in neighbors(position) {
+                let light = light_at(world, neighbor);
+                if light == 0 {
+                    continue;
+                }
+                if light < level {
+                    self.set(world, neighbor, 0);
+                    self.removed.push((neighbor, light));
+
+                    let emission = emission_at(world, neighbor);
+                    if emission > 0 {
+                        self.set(world, neighbor, emission);
+                        self.queue_spread(neighbor, emission);
+                    }
+                } else {
+                    self.queue_spread(neighbor, light);
+                }
+            }
+        }
+    }
+
+    /// Floods the queued light outward, one level dimmer per block.
+    fn spread(&mut self, world: &mut World) {
+        for level in (2..=MAX_LIGHT).rev() {
+            while let Some(position) = self.spread[level as usize].pop() {
+                // A brighter source reached this block after it was queued
+                if light_at(world, position) != level {
+                    continue;
+                }
+
+                for neighbor in neighbors(position) {
+                    if is_open(world, neighbor) && light_at(world, neighbor) < level - 1 {
+                        self.set(world, neighbor, level - 1);
+                        self.queue_spread(neighbor, level - 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Queues the chunks whose light changed for re-meshing.
+    fn queue_meshes(self, world: &mut World) {
+        for coord in self.changed {
+            world.queue_mesh(coord);
+        }
+    }
+}
+
+/// Computes the block light of a newly loaded chunk.
+///
+/// The chunk's emitters are spread, along with the light shining in from the loaded
+/// neighbors, and the chunk's light spreads on into the neighbors where it is brighter.
+///
+/// # Arguments
+///
+/// * `world` - A mutable reference to the game world.
+/// * `coord` - The coordinates of the loaded chunk.
+pub fn light_chunk(world: &mut World, coord: (i32, i32)) {
+    let Some(chunk) = world.chunks.get(&coord) else {
+        return;
+    };
+    let origin = [coord.0 * CHUNK_SIZE as i32, coord.1 * CHUNK_SIZE as i32];
+
+    let mut emitters = Vec::new();
+    for x in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_SIZE {
+                let emission = Block::new(chunk.get_block(x, y, z)).light_emission();
+                if emission > 0 {
+                    let position = [origin[0] + x as i32, y as i32, origin[1] + z as i32];
+                    emitters.push((position, emission));
+                }
+            }
+        }
+    }
+
+    let mut flood = LightFlood::new();
+    for (position, emission) in emitters {
+        if emission > light_at(world, position) {
+            flood.set(world, position, emission);
+        }
+        flood.queue_spread(position, light_at(world, position));
+    }
+
+    // The blocks of the neighbors along the shared borders
+    let size = CHUNK_SIZE as i32;
+    for i in 0..size {
+        for y in 0..CHUNK_HEIGHT as i32 {
+            for position in [
+                [origin[0] - 1, y, origin[1] + i],
+                [origin[0] + size, y, origin[1] + i],
+                [origin[0] + i, y, origin[1] - 1],
+                [origin[0] + i, y, origin[1] + size],
+            ] {
+                flood.queue_spread(position, light_at(world, position));
+            }
+        }
+    }
+
+    flood.spread(world);
+    flood.queue_meshes(world);
+}
+
+/// Updates the block light around a block that changed.
+///
+/// The light the block had is taken away from everything lit through it, then the
+/// remaining sources, and the block itself if it emits light, spread into the cleared
+/// blocks again. Removing one source this way leaves the light of the others intact.
+///
+/// # Arguments
+///
+/// * `world` - A mutable reference to the game world.
+/// * `position` - The world block coordinates of the changed block.
+pub fn update_block_light(world: &mut World, position: [i32; 3]) {
+    let mut flood = LightFlood::new();
+
+    let old = light_at(world, position);
+    if old > 0 {
+        flood.set(world, position, 0);
+        flood.removed.push((position, old));
+        flood.remove(world);
+    }
+
+    let emission = emission_at(world, position);
+    if emission > light_at(world, position) {
+        flood.set(world, position, emission);
+        flood.queue_spread(position, emission);
+    }
+
+    // Light shines into the block from its neighbors if it lets light through
+    if is_open(world, position) {
+        for neighbor in neighbors(position) {
+            flood.queue_spread(neighbor, light_at(world, neighbor));
+        }
+    }
+
+    flood.spread(world);
+    flood.queue_meshes(world);
+}
+
+/// Updates the light around every block changed since the last update, in edit order.
+///
+/// # Arguments
+///
+/// * `world` - A mutable reference to the game world.
+pub fn update_changed_blocks(world: &mut World) {
+    for position in std::mem::take(&mut world.block_light_updates) {
+        update_block_light(world, position);
+    }
+}
+
+/// Returns the six blocks sharing a face with a block.
+fn neighbors([x, y, z]: [i32; 3]) -> impl Iterator<Item = [i32; 3]> {
+    NEIGHBORS
+        .iter()
+        .map(move |[dx, dy, dz]| [x + dx, y + dy, z + dz])
+}
+
+/// Returns the block light at a position, or `0` outside the loaded world.
+fn light_at(world: &World, [x, y, z]: [i32; 3]) -> u8 {
+    world.get_light(x, y, z)
+}
+
+/// Returns the light a block emits.
+fn emission_at(world: &World, [x, y, z]: [i32; 3]) -> u8 {
+    Block::new(world.get_block(x, y, z)).light_emission()
+}
+
+/// Returns whether light passes through a block. Blocks outside the loaded world don't
+/// take light, so it stops at the edge of the loaded chunks.
+fn is_open(world: &World, [x, y, z]: [i32; 3]) -> bool {
+    if y < 0 || y >= CHUNK_HEIGHT as i32 || !world.chunks.contains_key(&chunk_coords(x, z)) {
+        return false;
+    }
+    let block = Block::new(world.get_block(x, y, z));
+    !block.is_full_cube() || block.is_transparent()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::chunk_mesh::VERTEX_STRIDE;
+    use crate::world::chunk::Chunk;
+    use crate::world::meshing::build_chunk_vertices;
+
+    /// Returns the summed color of the vertices of a chunk, which grows with the light.
+    fn brightness(world: &World, chunk_x: i32, chunk_z: i32) -> f32 {
+        let vertices = build_chunk_vertices(world, chunk_x, chunk_z, false, false).opaque;
+        vertices
+            .chunks(VERTEX_STRIDE)
+            .map(|vertex| vertex[3] + vertex[4] + vertex[5])
+            .sum()
+    }
+
+    #[test]
+    fn torch_lights_the_neighboring_chunk() {
+        let mut world = World::with_empty_chunks(&[(0, 0), (1, 0)]);
+        // A stone block in chunk (1, 0), two blocks from a torch at the edge of chunk (0, 0)
+        world.set_block(17, 9, 5, 3);
+        update_changed_blocks(&mut world);
+        let unlit = brightness(&world, 1, 0);
+
+        world.set_block(15, 10, 5, 10);
+        update_changed_blocks(&mut world);
+
+        assert!(world.get_light(17, 10, 5) > 0);
+        assert!(brightness(&world, 1, 0) > unlit);
+    }
+
+    #[test]
+    fn overlapping_torches_keep_the_brightest_level() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        let torch = Block::new(10).light_emission();
+        world.set_block(4, 10, 8, 10);
+        world.set_block(6, 10, 8, 10);
+        update_changed_blocks(&mut world);
+
+        // Both torches are one block from x = 5, which gets one level less, not the sum
+        assert_eq!(world.get_light(5, 10, 8), torch - 1);
+        assert_eq!(world.get_light(9, 10, 8), torch - 3);
+
+        world.set_block(6, 10, 8, 0);
+        update_changed_blocks(&mut world);
+
+        assert_eq!(world.get_light(5, 10, 8), torch - 1);
+        assert_eq!(world.get_light(6, 10, 8), torch - 2);
+        assert_eq!(world.get_light(9, 10, 8), torch - 5);
+    }
+
+    #[test]
+    fn torches_on_both_sides_of_a_chunk_border_overlap() {
+        let mut world = World::with_empty_chunks(&[(0, 0), (1, 0)]);
+        let torch = Block::new(10).light_emission();
+        world.set_block(14, 10, 8, 10);
+        world.set_block(18, 10, 8, 10);
+        update_changed_blocks(&mut world);
+
+        // x = 16 is two blocks from both torches and gets the level of one, not the sum
+        assert_eq!(world.get_light(15, 10, 8), torch - 1);
+        assert_eq!(world.get_light(16, 10, 8), torch - 2);
+        assert_eq!(world.get_light(17, 10, 8), torch - 1);
+
+        // Removing the torch in chunk (1, 0) leaves the light of the other across the border
+        world.set_block(18, 10, 8, 0);
+        update_changed_blocks(&mut world);
+
+        assert_eq!(world.get_light(16, 10, 8), torch - 2);
+        assert_eq!(world.get_light(18, 10, 8), torch - 4);
+        assert_eq!(world.get_light(20, 10, 8), torch - 6);
+    }
+
+    #[test]
+    fn torch_only_remeshes_the_chunks_its_light_reaches() {
+        let mut world = World::with_empty_chunks(&[(-1, 0), (0, 0), (1, 0)]);
+
+        // Light fades out within 13 blocks, before reaching chunk (1, 0)
+        world.set_block(1, 10, 8, 10);
+        world.mesh_updates.clear();
+        update_changed_blocks(&mut world);
+
+        let mut remeshed: Vec<(i32, i32)> = world.mesh_updates.iter().copied().collect();
+        remeshed.sort();
+        assert_eq!(remeshed, [(-1, 0), (0, 0)]);
+
+        world.set_block(1, 10, 8, 0);
+        update_changed_blocks(&mut world);
+        assert_eq!(world.get_light(-5, 10, 8), 0);
+    }
+
+    #[test]
+    fn loaded_chunk_takes_light_from_its_neighbor() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        let torch = Block::new(10).light_emission();
+        world.set_block(15, 10, 5, 10);
+        update_changed_blocks(&mut world);
+
+        world
+            .chunks
+            .insert((1, 0), Chunk::new(crate::world::chunk::ChunkData::new()));
+        light_chunk(&mut world, (1, 0));
+
+        assert_eq!(world.get_light(16, 10, 5), torch - 1);
+        assert_eq!(world.get_light(18, 10, 5), torch - 3);
+    }
+}
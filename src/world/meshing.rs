@@ -0,0 +1,503 @@
+use crate::world::block::{Block, BlockBox};
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::world::lighting::MAX_LIGHT;
+use crate::world::world::World;
+
+/// The six faces of a unit cube, as the offset to the neighboring block and the face's
+/// four corners in counter-clockwise order.
+const FACES: [([i32; 3], [[f32; 3]; 4]); 6] = [
+    // Right face (+X)
+    (
+        [1, 0, 0],
+        [
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 1.0, 1.0],
+            [1.0, 0.0, 1.0],
+        ],
+    ),
+    // Left face (-X)
+    (
+        [-1, 0, 0],
+        [
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+        ],
+    ),
+    // Top face (+Y)
+    (
+        [0, 1, 0],
+        [
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [1.0, 1.0, 0.0],
+        ],
+    ),
+    // Bottom face (-Y)
+    (
+        [0, -1, 0],
+        [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0],
+        ],
+    ),
+    // Front face (+Z)
+    (
+        [0, 0, 1],
+        [
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [0.0, 1.0, 1.0],
+        ],
+    ),
+    // Back face (-Z)
+    (
+        [0, 0, -1],
+        [
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ],
+    ),
+];
+
+/// The corner order used to split a face into two triangles.
+const FACE_TRIANGLES: [usize; 6] = [0, 1, 2, 0, 2, 3];
+
+/// The warm tint added to faces at full block light.
+const LIGHT_TINT: [f32; 3] = [0.45, 0.35, 0.15];
+
+/// How bright a face corner is with no, one, two or three occluding blocks around it.
+const AO_SHADES: [f32; 4] = [1.0, 0.8, 0.65, 0.5];
+
+/// The vertex data of a chunk, split by whether the faces need blending.
+#[derive(Default)]
+pub struct ChunkVertices {
+    /// Faces drawn without blending.
+    pub opaque: Vec<f32>,
+    /// Faces with a color alpha below one, drawn with blending after all opaque faces.
+    pub translucent: Vec<f32>,
+}
+
+/// A stack of identical side faces along Y, merged into a single quad.
+///
+/// Faces only join a run when they belong to the same kind of block and receive the same
+/// light, so merging never smears shading across the quad.
+#[derive(Clone, Copy)]
+struct FaceRun {
+    /// The y coordinate of the lowest face.
+    start_y: usize,
+    /// The number of stacked faces.
+    height: usize,
+    /// The id of the blocks the faces belong to.
+    block_id: u32,
+    /// The block light shining on each of the faces.
+    light: u8,
+    color: [f32; 4],
+}
+
+impl FaceRun {
+    /// Returns whether a face of the given block and light can extend the run.
+    fn accepts(&self, block_id: u32, light: u8) -> bool {
+        self.block_id == block_id && self.light == light
+    }
+}
+
+impl ChunkVertices {
+    /// Returns the vertex list a face with the given color belongs in.
+    fn for_color(&mut self, color: [f32; 4]) -> &mut Vec<f32> {
+        if color[3] < 1.0 {
+            &mut self.translucent
+        } else {
+            &mut self.opaque
+        }
+    }
+}
+
+/// Builds the vertex data for the chunk at the given chunk coordinates.
+///
+/// Only faces that aren't covered by a full neighboring block are emitted, so blocks that are
+/// buried inside the terrain produce no geometry. Transparent neighbors such as water only
+/// cover faces of the same kind of block. Partial blocks such as slabs are meshed
+/// from their shape boxes. Faces on the chunk border are culled against the neighboring
+/// chunk when it is loaded.
+///
+/// Side faces of full blocks stacked on top of each other are merged into one tall quad
+/// when they belong to the same kind of block and have the same light, so cliffs and walls
+/// cost a few quads instead of one per block. Faces darkened by ambient occlusion are
+/// never merged.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `chunk_x` - The x coordinate of the chunk.
+/// * `chunk_z` - The z coordinate of the chunk.
+/// * `cull_world_bottom` - Whether to skip the downward faces of the lowest layer of the
+///   world, which can never be seen from below. Blocks above the bottom layer, such as
+///   floating islands, keep their downward faces.
+/// * `ambient_occlusion` - Whether to darken the corners of full block faces by the opaque
+///   blocks around them, so creases and corners read as shaded.
+///
+/// # Returns
+///
+/// Interleaved vertex data (position and color) in chunk-local coordinates.
+pub fn build_chunk_vertices(
+    world: &World,
+    chunk_x: i32,
+    chunk_z: i32,
+    cull_world_bottom: bool,
+    ambient_occlusion: bool,
+) -> ChunkVertices {
+    let mut vertices = ChunkVertices::default();
+    let Some(chunk) = world.chunks.get(&(chunk_x, chunk_z)) else {
+        return vertices;
+    };
+
+    let origin_x = chunk_x * CHUNK_SIZE as i32;
+    let origin_z = chunk_z * CHUNK_SIZE as i32;
+
+    for x in 0..CHUNK_SIZE {
+        for z in 0..CHUNK_SIZE {
+            let (world_x, world_z) = (origin_x + x as i32, origin_z + z as i32);
+
+            // The side faces of the blocks below that may continue upward, indexed like FACES
+            let mut runs: [Option<FaceRun>; 6] = [None; 6];
+
+            for y in 0..CHUNK_HEIGHT {
+                let mut continued = [false; 6];
+                let block = Block::new(chunk.get_block(x, y, z));
+                let full_cube = block.is_full_cube();
+
+                for shape in block.collision_boxes() {
+                    for (face, (offset, corners)) in FACES.iter().enumerate() {
+                        // Faces are lit by the block they face; faces inside the cell can't
+                        // be covered by the neighbor and are lit by the cell itself
+                        let on_boundary = on_cell_boundary(shape, *offset);
+                        let light = if on_boundary {
+                            if cull_world_bottom && offset[1] < 0 && y == 0 {
+                                continue;
+                            }
+                            let (neighbor_x, neighbor_y, neighbor_z) = (
+                                world_x + offset[0],
+                                y as i32 + offset[1],
+                                world_z + offset[2],
+                            );
+                            let neighbor =
+                                Block::new(world.get_block(neighbor_x, neighbor_y, neighbor_z));
+                            if block.is_face_hidden_by(&neighbor) {
+                                continue;
+                            }
+                            world.get_light(neighbor_x, neighbor_y, neighbor_z)
+                        } else {
+                            world.get_light(world_x, y as i32, world_z)
+                        };
+
+                        let color = apply_light(block.get_face_color(*offset), light);
+                        let shade = if ambient_occlusion && full_cube && on_boundary {
+                            corner_shades(world, [world_x, y as i32, world_z], *offset, corners)
+                        } else {
+                            [1.0; 4]
+                        };
+
+                        // Grow the run of matching faces below, or start a new one
+                        if full_cube && offset[1] == 0 && shade == [1.0; 4] {
+                            match &mut runs[face] {
+                                Some(run) if run.accepts(block.id, light) => run.height += 1,
+                                run => {
+                                    if let Some(finished) = run.take() {
+                                        push_run(&mut vertices, [x, z], face, finished);
+                                    }
+                                    *run = Some(FaceRun {
+                                        start_y: y,
+                                        height: 1,
+                                        block_id: block.id,
+                                        light,
+                                        color,
+                                    });
+                                }
+                            }
+                            continued[face] = true;
+                            continue;
+                        }
+
+                        push_shaded_face(
+                            vertices.for_color(color),
+                            [x as f32, y as f32, z as f32],
+                            &shape_corners(shape, corners),
+                            color,
+                            shade,
+                        );
+                    }
+                }
+
+                // Runs that don't continue into this block are complete
+                for (face, run) in runs.iter_mut().enumerate() {
+                    if !continued[face]
+                        && let Some(finished) = run.take()
+                    {
+                        push_run(&mut vertices, [x, z], face, finished);
+                    }
+                }
+            }
+
+            for (face, run) in runs.into_iter().enumerate() {
+                if let Some(finished) = run {
+                    push_run(&mut vertices, [x, z], face, finished);
+                }
+            }
+        }
+    }
+
+    vertices
+}
+
+/// Appends a run of stacked side faces as a single quad.
+fn push_run(vertices: &mut ChunkVertices, column: [usize; 2], face: usize, run: FaceRun) {
+    let shape = BlockBox {
+        min: [0.0, 0.0, 0.0],
+        max: [1.0, run.height as f32, 1.0],
+    };
+    push_face(
+        vertices.for_color(run.color),
+        [column[0] as f32, run.start_y as f32, column[1] as f32],
+        &shape_corners(&shape, &FACES[face].1),
+        run.color,
+    );
+}
+
+/// Builds the vertex data for a single, unculled block at the origin.
+///
+/// Used for previews such as the placement ghost. The face colors are blended toward white
+/// so the preview is easy to tell apart from real blocks.
+///
+/// # Arguments
+///
+/// * `block_id` - The id of the block.
+/// * `alpha` - The opacity of the block's faces.
+///
+/// # Returns
+///
+/// Interleaved vertex data (position and color) for the block's shape within `[0, 1]`.
+pub fn build_block_vertices(block_id: u32, alpha: f32) -> Vec<f32> {
+    let mut vertices = Vec::new();
+    let block = Block::new(block_id);
+
+    for shape in block.collision_boxes() {
+        for (offset, corners) in FACES.iter() {
+            let face_color = block.get_face_color(*offset);
+            let color = [
+                (face_color[0] + 1.0) / 2.0,
+                (face_color[1] + 1.0) / 2.0,
+                (face_color[2] + 1.0) / 2.0,
+                alpha,
+            ];
+            push_face(
+                &mut vertices,
+                [0.0, 0.0, 0.0],
+                &shape_corners(shape, corners),
+                color,
+            );
+        }
+    }
+
+    vertices
+}
+
+/// Brightens a face color by the block light shining on it.
+fn apply_light(color: [f32; 4], light: u8) -> [f32; 4] {
+    let strength = light as f32 / MAX_LIGHT as f32;
+    [
+        (color[0] + LIGHT_TINT[0] * strength).min(1.0),
+        (color[1] + LIGHT_TINT[1] * strength).min(1.0),
+        (color[2] + LIGHT_TINT[2] * strength).min(1.0),
+        color[3],
+    ]
+}
+
+/// Returns the ambient occlusion shade of each corner of a full block face.
+///
+/// Each corner is darkened by the opaque full blocks in front of the face that touch it:
+/// the two along the face edges and the one diagonally across the corner. Two edge blocks
+/// fully enclose the corner, whatever the diagonal block is.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `position` - The world block coordinates of the block the face belongs to.
+/// * `normal` - The normal of the face.
+/// * `corners` - The corners of the unit cube face, as in `FACES`.
+fn corner_shades(
+    world: &World,
+    position: [i32; 3],
+    normal: [i32; 3],
+    corners: &[[f32; 3]; 4],
+) -> [f32; 4] {
+    let occludes = |offset: [i32; 3]| {
+        let block = Block::new(world.get_block(
+            position[0] + normal[0] + offset[0],
+            position[1] + normal[1] + offset[1],
+            position[2] + normal[2] + offset[2],
+        ));
+        block.is_solid() && block.is_full_cube() && !block.is_transparent()
+    };
+
+    corners.map(|corner| {
+        // Step toward the corner along the two axes the face spans
+        let [first, second] = match normal.iter().position(|&n| n != 0) {
+            Some(0) => [1, 2],
+            Some(1) => [0, 2],
+            _ => [0, 1],
+        };
+        let step = |axis: usize| {
+            let mut offset = [0; 3];
+            offset[axis] = if corner[axis] > 0.5 { 1 } else { -1 };
+            offset
+        };
+        let (a, b) = (step(first), step(second));
+        let side_a = occludes(a);
+        let side_b = occludes(b);
+        let diagonal = occludes([a[0] + b[0], a[1] + b[1], a[2] + b[2]]);
+        let level = if side_a && side_b {
+            3
+        } else {
+            side_a as usize + side_b as usize + diagonal as usize
+        };
+        AO_SHADES[level]
+    })
+}
+
+/// Returns whether the face of a shape box with the given normal lies on the cell boundary.
+fn on_cell_boundary(shape: &BlockBox, normal: [i32; 3]) -> bool {
+    (0..3).all(|axis| match normal[axis] {
+        1 => shape.max[axis] >= 1.0,
+        -1 => shape.min[axis] <= 0.0,
+        _ => true,
+    })
+}
+
+/// Maps the corners of a unit cube face onto the matching face of a shape box.
+fn shape_corners(shape: &BlockBox, corners: &[[f32; 3]; 4]) -> [[f32; 3]; 4] {
+    corners.map(|corner| {
+        [0, 1, 2].map(|axis| shape.min[axis] + corner[axis] * (shape.max[axis] - shape.min[axis]))
+    })
+}
+
+/// Appends the two triangles of a block face to the vertex data.
+fn push_face(
+    vertices: &mut Vec<f32>,
+    position: [f32; 3],
+    corners: &[[f32; 3]; 4],
+    color: [f32; 4],
+) {
+    push_shaded_face(vertices, position, corners, color, [1.0; 4]);
+}
+
+/// Appends the two triangles of a block face, with the color of each corner scaled by its
+/// shade.
+fn push_shaded_face(
+    vertices: &mut Vec<f32>,
+    position: [f32; 3],
+    corners: &[[f32; 3]; 4],
+    color: [f32; 4],
+    shade: [f32; 4],
+) {
+    for &corner in FACE_TRIANGLES.iter() {
+        let offset = corners[corner];
+        vertices.extend_from_slice(&[
+            position[0] + offset[0],
+            position[1] + offset[1],
+            position[2] + offset[2],
+            color[0] * shade[corner],
+            color[1] * shade[corner],
+            color[2] * shade[corner],
+            color[3],
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::chunk_mesh::VERTEX_STRIDE;
+
+    /// Returns the faces of the given block, as the positions of their six vertices.
+    fn faces_of(vertices: &[f32], block_id: u32) -> Vec<Vec<[f32; 3]>> {
+        let color = Block::new(block_id).get_color();
+        let positions: Vec<[f32; 3]> = vertices
+            .chunks(VERTEX_STRIDE)
+            .filter(|vertex| vertex[3..7] == color)
+            .map(|vertex| [vertex[0], vertex[1], vertex[2]])
+            .collect();
+        positions
+            .chunks(FACE_TRIANGLES.len())
+            .map(|face| face.to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn stone_face_is_drawn_against_water() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.set_block(5, 5, 5, 3);
+        world.set_block(6, 5, 5, 5);
+
+        let vertices = build_chunk_vertices(&world, 0, 0, false, false).opaque;
+        let stone = faces_of(&vertices, 3);
+        let water = faces_of(&vertices, 5);
+        let on_interface =
+            |faces: &[Vec<[f32; 3]>]| faces.iter().any(|face| face.iter().all(|p| p[0] == 6.0));
+
+        assert_eq!(stone.len(), 6);
+        assert!(on_interface(&stone));
+        assert_eq!(water.len(), 5);
+        assert!(!on_interface(&water));
+    }
+
+    #[test]
+    fn stacked_faces_with_different_light_are_not_merged() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.set_block(5, 5, 5, 3);
+        world.set_block(5, 6, 5, 3);
+        let merged = build_chunk_vertices(&world, 0, 0, false, false).opaque;
+
+        // Light only the air in front of the lower block's +X face
+        world.chunks.get_mut(&(0, 0)).unwrap().light[6][5][5] = MAX_LIGHT;
+        let split = build_chunk_vertices(&world, 0, 0, false, false).opaque;
+
+        let side_quads = |vertices: &[f32]| {
+            let positions: Vec<f32> = vertices.chunks(VERTEX_STRIDE).map(|v| v[0]).collect();
+            positions
+                .chunks(FACE_TRIANGLES.len())
+                .filter(|face| face.iter().all(|&x| x == 6.0))
+                .count()
+        };
+        assert_eq!(side_quads(&merged), 1);
+        assert_eq!(side_quads(&split), 2);
+    }
+
+    #[test]
+    fn corners_next_to_a_wall_are_shaded() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.set_block(5, 5, 5, 3);
+        // A wall block on top of the +X neighbor shades the upper corners of the top face
+        // toward it, but not the corners away from it
+        world.set_block(6, 6, 5, 3);
+
+        let top = &FACES[2];
+        let shades = corner_shades(&world, [5, 5, 5], top.0, &top.1);
+        let edge = AO_SHADES[1];
+        assert_eq!(shades, [1.0, 1.0, edge, edge]);
+
+        // Occlusion only changes the colors, not the faces
+        let vertices = build_chunk_vertices(&world, 0, 0, false, true).opaque;
+        let plain = build_chunk_vertices(&world, 0, 0, false, false).opaque;
+        assert_eq!(vertices.len(), plain.len());
+        assert_ne!(vertices, plain);
+    }
+}
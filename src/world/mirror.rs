@@ -0,0 +1,46 @@
+/// The horizontal axis a mirror plane is perpendicular to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MirrorAxis {
+    /// The plane faces along X, mirroring blocks from east to west.
+    X,
+    /// The plane faces along Z, mirroring blocks from south to north.
+    Z,
+}
+
+/// A vertical plane that player edits are mirrored across.
+///
+/// The plane runs through the middle of a column of blocks, so blocks in that column map
+/// onto themselves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MirrorPlane {
+    /// The axis the plane is perpendicular to.
+    pub axis: MirrorAxis,
+    /// The block coordinate along `axis` of the column the plane runs through.
+    pub coordinate: i32,
+}
+
+impl MirrorPlane {
+    /// Returns the mirror image of a block position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The world block coordinates to mirror.
+    ///
+    /// # Returns
+    ///
+    /// The mirrored block coordinates, or `None` if the block lies on the plane and is its
+    /// own mirror image.
+    pub fn mirror(&self, position: [i32; 3]) -> Option<[i32; 3]> {
+        let axis = match self.axis {
+            MirrorAxis::X => 0,
+            MirrorAxis::Z => 2,
+        };
+        if position[axis] == self.coordinate {
+            return None;
+        }
+
+        let mut mirrored = position;
+        mirrored[axis] = 2 * self.coordinate - position[axis];
+        Some(mirrored)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::config::Config;
+use crate::rendering::shader::Shader;
+use crate::world::block::Block;
+use crate::world::chunk::chunk_coords;
+use crate::world::generation::{Rng, Terrain};
+use crate::world::world::World;
+use cgmath::{Matrix4, Point3, Rad, Vector3};
+use std::f32::consts::TAU;
+
+/// The width and depth of a mob's body, in blocks.
+const BODY_WIDTH: f32 = 0.8;
+
+/// The height of a mob's body, in blocks.
+const BODY_HEIGHT: f32 = 0.9;
+
+/// The edge length of a mob's head, which sits on the front of the body, in blocks.
+const HEAD_SIZE: f32 = 0.5;
+
+/// The colors of a mob's body and head.
+const MOB_COLORS: ([f32; 4], [f32; 4]) = ([0.9, 0.75, 0.6, 1.0], [0.75, 0.55, 0.45, 1.0]);
+
+/// How fast mobs walk, in blocks per second.
+const WALK_SPEED: f32 = 1.5;
+
+/// The downward acceleration of mobs, in blocks per second squared.
+const GRAVITY: f32 = 24.0;
+
+/// The upward speed of a mob's jump onto a one-block step, in blocks per second.
+const JUMP_SPEED: f32 = 7.5;
+
+/// The deepest drop a mob walks off, in blocks.
+const MAX_DROP: i32 = 3;
+
+/// The shortest and longest time a mob keeps walking or standing before it decides again,
+/// in seconds.
+const WANDER_TIME_RANGE: (f32, f32) = (1.0, 4.0);
+
+/// The chance that a mob stands still instead of picking a new direction.
+const IDLE_CHANCE: f32 = 0.3;
+
+/// The time between spawn attempts, in seconds.
+const SPAWN_INTERVAL: f32 = 3.0;
+
+/// The nearest and farthest distance from the player mobs spawn at, in blocks.
+const SPAWN_DISTANCE_RANGE: (f32, f32) = (8.0, 24.0);
+
+/// A simple creature that wanders across the terrain.
+struct Mob {
+    /// The center of the bottom of the body, in world units.
+    position: Point3<f32>,
+    /// The direction the mob faces, in radians around the Y axis.
+    heading: f32,
+    /// Whether the mob is walking toward its heading or standing still.
+    walking: bool,
+    /// The vertical speed of the mob, in world units per second.
+    vertical_velocity: f32,
+    /// Whether the mob stood on solid ground after its last move.
+    on_ground: bool,
+    /// The time left until the mob decides what to do next, in seconds.
+    wander_time: f32,
+}
+
+/// Spawns, moves and draws the mobs around the player.
+///
+/// Mobs appear on the surface of loaded chunks near the player, up to `world.max_mobs` at a
+/// time, and are removed once they are farther away than `world.mob_despawn_distance` or
+/// their chunk is unloaded.
+pub struct Mobs {
+    mobs: Vec<Mob>,
+    spawn_timer: f32,
+    rng: Rng,
+}
+
+impl Mobs {
+    /// Creates a new `Mobs` instance with no mobs.
+    ///
+    /// # Returns
+    ///
+    /// A new `Mobs` instance.
+    pub fn new() -> Self {
+        Mobs {
+            mobs: Vec::new(),
+            spawn_timer: 0.0,
+            rng: Rng::new(0x2545_F491),
+        }
+    }
+
+    /// Spawns and despawns mobs around the player and moves the ones that are left.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A reference to the game world.
+    /// * `player` - The player's feet position, in world units.
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(&mut self, world: &World, player: Point3<f32>, delta_time: f32, config: &Config) {
+        // Forget mobs that wandered off, fell out of the world or lost their chunk
+        let despawn_distance = config.world.mob_despawn_distance * world.block_size;
+        self.mobs.retain(|mob| {
+            let block = world.to_block_space(mob.position);
+            let offset = Vector3::new(mob.position.x - player.x, 0.0, mob.position.z - player.z);
+            offset.x.hypot(offset.z) <= despawn_distance
+                && block.y >= 0.0
+                && world.chunks.contains_key(&chunk_coords(
+                    block.x.floor() as i32,
+                    block.z.floor() as i32,
+                ))
+        });
+
+        self.spawn_timer += delta_time;
+        if self.spawn_timer >= SPAWN_INTERVAL {
+            self.spawn_timer = 0.0;
+            if self.mobs.len() < config.world.max_mobs {
+                self.try_spawn(world, player, config);
+            }
+        }
+
+        let terrain = Terrain::new(world.seed, &config.world);
+        for index in 0..self.mobs.len() {
+            self.wander(index, delta_time);
+            Self::move_mob(&mut self.mobs[index], world, terrain, delta_time);
+        }
+    }
+
+    /// Tries to spawn a mob on the surface at a random spot near the player.
+    fn try_spawn(&mut self, world: &World, player: Point3<f32>, config: &Config) {
+        let angle = self.rng.next_f32() * TAU;
+        let (min, max) = SPAWN_DISTANCE_RANGE;
+        let distance = min + self.rng.next_f32() * (max - min);
+        let center = world.to_block_space(player);
+        let x = (center.x + angle.cos() * distance).floor() as i32;
+        let z = (center.z + angle.sin() * distance).floor() as i32;
+
+        // Only spawn on dry, solid ground of loaded terrain
+        if !world.chunks.contains_key(&chunk_coords(x, z)) {
+            return;
+        }
+        let surface = world.surface_height(x, z, Terrain::new(world.seed, &config.world));
+        let ground = Block::new(world.get_block(x, surface, z));
+        if surface < 0 || !ground.is_full_cube() || ground.water_level() > 0 {
+            return;
+        }
+
+        let wander_time = self.random_wander_time();
+        let heading = self.rng.next_f32() * TAU;
+        self.mobs.push(Mob {
+            position: Point3::new(x as f32 + 0.5, (surface + 1) as f32, z as f32 + 0.5)
+                * world.block_size,
+            heading,
+            walking: false,
+            vertical_velocity: 0.0,
+            on_ground: true,
+            wander_time,
+        });
+    }
+
+    /// Counts down a mob's current action, and picks a new one when it is over.
+    fn wander(&mut self, index: usize, delta_time: f32) {
+        self.mobs[index].wander_time -= delta_time;
+        if self.mobs[index].wander_time > 0.0 {
+            return;
+        }
+
+        let walking = self.rng.next_f32() >= IDLE_CHANCE;
+        let heading = self.rng.next_f32() * TAU;
+        let wander_time = self.random_wander_time();
+        let mob = &mut self.mobs[index];
+        mob.walking = walking;
+        if walking {
+            mob.heading = heading;
+        }
+        mob.wander_time = wander_time;
+    }
+
+    /// Moves a mob toward its heading under gravity, turning it around at cliffs, walls
+    /// and water.
+    fn move_mob(mob: &mut Mob, world: &World, terrain: Terrain, delta_time: f32) {
+        let block_size = world.block_size;
+        let radius = BODY_WIDTH / 2.0 * block_size;
+        let height = BODY_HEIGHT * block_size;
+        let mut target = mob.position;
+
+        if mob.walking {
+            let step = WALK_SPEED * block_size * delta_time;
+            let direction = Vector3::new(mob.heading.cos(), 0.0, mob.heading.sin());
+            let ahead = world.to_block_space(mob.position + direction * (radius + step));
+            let (x, z) = (ahead.x.floor() as i32, ahead.z.floor() as i32);
+
+            // Look at the column in front to decide whether it can be walked onto
+            let feet_y = world.to_block_space(mob.position).y.floor() as i32;
+            let surface = world.surface_height(x, z, terrain);
+            let ground = Block::new(world.get_block(x, surface, z));
+            let rise = surface + 1 - feet_y;
+            if !(-MAX_DROP..=1).contains(&rise) || ground.water_level() > 0 {
+                mob.heading = (mob.heading + TAU / 2.0) % TAU;
+            } else {
+                if rise == 1 && mob.on_ground {
+                    mob.vertical_velocity = JUMP_SPEED * block_size;
+                    mob.on_ground = false;
+                }
+                target += direction * step;
+            }
+        }
+
+        mob.vertical_velocity -= GRAVITY * block_size * delta_time;
+        target.y += mob.vertical_velocity * delta_time;
+
+        // The position is the bottom of the body, so there is no eye offset
+        let resolved = world.resolve_collision(mob.position, target, radius, height, 0.0);
+        let ground_check = Point3::new(resolved.x, resolved.y - 0.05 * block_size, resolved.z);
+        mob.on_ground = world.check_collision(&ground_check, radius, 0.1);
+        if mob.on_ground && mob.vertical_velocity < 0.0 {
+            mob.vertical_velocity = 0.0;
+        }
+        mob.position = resolved;
+    }
+
+    /// Draws every mob as a body with a head on the side it faces.
+    ///
+    /// The projection and view matrices of the shader must already be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `shader` - The shader to use for rendering, with a uniform color.
+    /// * `world` - A reference to the game world, whose cube mesh is drawn.
+    pub fn draw(&self, shader: &Shader, world: &World) {
+        if self.mobs.is_empty() {
+            return;
+        }
+
+        shader.use_program();
+        let (body_color, head_color) = MOB_COLORS;
+
+        for mob in self.mobs.iter() {
+            let base = world.to_block_space(mob.position);
+            let body = world.block_model(Vector3::new(base.x, base.y + BODY_HEIGHT / 2.0, base.z))
+                * Matrix4::from_nonuniform_scale(BODY_WIDTH, BODY_HEIGHT, BODY_WIDTH);
+
+            let forward = (BODY_WIDTH + HEAD_SIZE) / 2.0 - HEAD_SIZE / 4.0;
+            let head = world.block_model(Vector3::new(
+                base.x + mob.heading.cos() * forward,
+                base.y + BODY_HEIGHT,
+                base.z + mob.heading.sin() * forward,
+            )) * Matrix4::from_angle_y(Rad(-mob.heading))
+                * Matrix4::from_scale(HEAD_SIZE);
+
+            for (model, color) in [(body, body_color), (head, head_color)] {
+                shader.set_mat4("model", &model);
+                shader.set_vec4("color", color);
+                world.mesh.draw();
+            }
+        }
+    }
+
+    /// Returns a random duration for a mob's next action.
+    fn random_wander_time(&mut self) -> f32 {
+        let (min, max) = WANDER_TIME_RANGE;
+        min + self.rng.next_f32() * (max - min)
+    }
+}
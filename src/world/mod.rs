@@ -1,5 +1,18 @@
 pub mod block;
+pub mod border;
+pub mod chunk;
+pub mod chunk_manager;
 pub mod cube_render;
+pub mod diagnostics;
 pub mod generation;
 pub mod init;
+pub mod lighting;
+pub mod meshing;
+pub mod mirror;
+pub mod mobs;
+pub mod raycast;
+pub mod save;
+pub mod spawn;
+pub mod ticking;
+#[allow(clippy::module_inception)]
 pub mod world;
@@ -0,0 +1,304 @@
+use crate::world::block::{Block, BlockBox};
+use crate::world::world::World;
+use cgmath::{InnerSpace, Point3, Vector3};
+
+/// One of the six faces of a block, named by its outward normal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaceDir {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl FaceDir {
+    /// Returns the face with the given outward normal.
+    ///
+    /// # Arguments
+    ///
+    /// * `normal` - A unit normal along one axis.
+    ///
+    /// # Returns
+    ///
+    /// The matching face, or `None` if the normal isn't a unit vector along an axis.
+    pub fn from_normal(normal: [i32; 3]) -> Option<Self> {
+        match normal {
+            [1, 0, 0] => Some(FaceDir::PosX),
+            [-1, 0, 0] => Some(FaceDir::NegX),
+            [0, 1, 0] => Some(FaceDir::PosY),
+            [0, -1, 0] => Some(FaceDir::NegY),
+            [0, 0, 1] => Some(FaceDir::PosZ),
+            [0, 0, -1] => Some(FaceDir::NegZ),
+            _ => None,
+        }
+    }
+
+    /// Returns the outward normal of the face.
+    pub fn normal(self) -> [i32; 3] {
+        match self {
+            FaceDir::PosX => [1, 0, 0],
+            FaceDir::NegX => [-1, 0, 0],
+            FaceDir::PosY => [0, 1, 0],
+            FaceDir::NegY => [0, -1, 0],
+            FaceDir::PosZ => [0, 0, 1],
+            FaceDir::NegZ => [0, 0, -1],
+        }
+    }
+
+    /// Returns a short label for the face, such as `+X`.
+    pub fn label(self) -> &'static str {
+        match self {
+            FaceDir::PosX => "+X",
+            FaceDir::NegX => "-X",
+            FaceDir::PosY => "+Y",
+            FaceDir::NegY => "-Y",
+            FaceDir::PosZ => "+Z",
+            FaceDir::NegZ => "-Z",
+        }
+    }
+}
+
+/// Represents a block hit by a raycast.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockHit {
+    /// The world block coordinates of the hit block.
+    pub position: [i32; 3],
+    /// The normal of the face that was hit, or all zeros if the ray started inside the block.
+    pub normal: [i32; 3],
+    /// The box of the block's shape that was hit, in block-local coordinates.
+    pub shape: BlockBox,
+    /// How far along the ray the block was hit, in world units.
+    pub distance: f32,
+}
+
+impl BlockHit {
+    /// Returns the face that was hit, or `None` if the ray started inside the block.
+    pub fn face(&self) -> Option<FaceDir> {
+        FaceDir::from_normal(self.normal)
+    }
+
+    /// Returns the block coordinates adjacent to the hit face, where a block would be placed.
+    pub fn adjacent_position(&self) -> [i32; 3] {
+        [
+            self.position[0] + self.normal[0],
+            self.position[1] + self.normal[1],
+            self.position[2] + self.normal[2],
+        ]
+    }
+}
+
+/// Casts a ray through the world and returns the first non-air block it hits.
+///
+/// Uses a voxel traversal (Amanatides & Woo), stepping one block boundary at a time, so no
+/// blocks along the ray are skipped regardless of distance. Partial blocks are only hit
+/// where the ray passes through one of their shape boxes. Transparent blocks such as glass
+/// and water are hit like any other block unless `through_transparent` is set, in which
+/// case the ray passes through them to the block behind.
+///
+/// # Arguments
+///
+/// * `world` - A reference to the game world.
+/// * `origin` - The start of the ray, in world units.
+/// * `direction` - The direction of the ray. Does not need to be normalized.
+/// * `max_distance` - The maximum distance to search, in world units.
+/// * `through_transparent` - Whether the ray passes through transparent blocks.
+///
+/// # Returns
+///
+/// The hit block, or `None` if no block was hit within `max_distance`.
+pub fn raycast(
+    world: &World,
+    origin: Point3<f32>,
+    direction: Vector3<f32>,
+    max_distance: f32,
+    through_transparent: bool,
+) -> Option<BlockHit> {
+    if direction.magnitude2() == 0.0 {
+        return None;
+    }
+
+    // Traverse in block space, where each block spans one unit
+    let origin = world.to_block_space(origin);
+    let max_distance = max_distance / world.block_size;
+
+    let direction = direction.normalize();
+    let origin = [origin.x, origin.y, origin.z];
+    let direction = [direction.x, direction.y, direction.z];
+
+    let mut cell = [
+        origin[0].floor() as i32,
+        origin[1].floor() as i32,
+        origin[2].floor() as i32,
+    ];
+    let mut step = [0; 3];
+    let mut t_max = [f32::INFINITY; 3];
+    let mut t_delta = [f32::INFINITY; 3];
+
+    for axis in 0..3 {
+        if direction[axis] > 0.0 {
+            step[axis] = 1;
+            t_delta[axis] = 1.0 / direction[axis];
+            t_max[axis] = (cell[axis] as f32 + 1.0 - origin[axis]) / direction[axis];
+        } else if direction[axis] < 0.0 {
+            step[axis] = -1;
+            t_delta[axis] = -1.0 / direction[axis];
+            t_max[axis] = (origin[axis] - cell[axis] as f32) / -direction[axis];
+        }
+    }
+
+    let mut normal = [0; 3];
+    // How far along the ray the current cell was entered
+    let mut t_entered = 0.0;
+
+    loop {
+        let block = Block::new(world.get_block(cell[0], cell[1], cell[2]));
+        // Transparent blocks can be looked through to target what is behind them
+        if !(through_transparent && block.is_transparent()) {
+            if block.is_full_cube() {
+                return Some(BlockHit {
+                    position: cell,
+                    normal,
+                    shape: BlockBox::FULL,
+                    distance: t_entered * world.block_size,
+                });
+            }
+
+            // Find the nearest shape box of a partial block the ray passes through
+            let nearest = block
+                .collision_boxes()
+                .iter()
+                .filter_map(|shape| {
+                    intersect_box(shape, cell, origin, direction)
+                        .filter(|(t, _)| *t <= max_distance)
+                        .map(|(t, box_normal)| (t, box_normal, *shape))
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((t, box_normal, shape)) = nearest {
+                return Some(BlockHit {
+                    position: cell,
+                    normal: box_normal,
+                    shape,
+                    distance: t.max(0.0) * world.block_size,
+                });
+            }
+        }
+
+        // Step across the nearest block boundary
+        let axis = if t_max[0] < t_max[1] {
+            if t_max[0] < t_max[2] { 0 } else { 2 }
+        } else if t_max[1] < t_max[2] {
+            1
+        } else {
+            2
+        };
+
+        if t_max[axis] > max_distance {
+            return None;
+        }
+
+        cell[axis] += step[axis];
+        t_entered = t_max[axis];
+        t_max[axis] += t_delta[axis];
+        normal = [0; 3];
+        normal[axis] = -step[axis];
+    }
+}
+
+/// Intersects a ray with a shape box of the block in the given cell.
+///
+/// # Returns
+///
+/// The distance along the ray to the box and the normal of the entered face (all zeros if
+/// the ray starts inside the box), or `None` if the ray misses it.
+fn intersect_box(
+    shape: &BlockBox,
+    cell: [i32; 3],
+    origin: [f32; 3],
+    direction: [f32; 3],
+) -> Option<(f32, [i32; 3])> {
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = [0; 3];
+
+    for axis in 0..3 {
+        let min = cell[axis] as f32 + shape.min[axis];
+        let max = cell[axis] as f32 + shape.max[axis];
+
+        if direction[axis] == 0.0 {
+            if origin[axis] < min || origin[axis] > max {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (min - origin[axis]) / direction[axis];
+        let t2 = (max - origin[axis]) / direction[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+        if near > t_enter {
+            t_enter = near;
+            normal = [0; 3];
+            normal[axis] = if direction[axis] > 0.0 { -1 } else { 1 };
+        }
+        t_exit = t_exit.min(far);
+    }
+
+    if t_enter > t_exit || t_exit < 0.0 {
+        return None;
+    }
+
+    if t_enter < 0.0 {
+        Some((0.0, [0; 3]))
+    } else {
+        Some((t_enter, normal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a world with a single stone block at (4, 4, 4).
+    fn world_with_block() -> World {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.set_block(4, 4, 4, 3);
+        world
+    }
+
+    #[test]
+    fn ray_from_above_hits_the_top_face() {
+        let world = world_with_block();
+        let origin = Point3::new(4.5, 8.0, 4.5);
+
+        let hit = raycast(&world, origin, Vector3::new(0.0, -1.0, 0.0), 10.0, false).unwrap();
+
+        assert_eq!(hit.position, [4, 4, 4]);
+        assert_eq!(hit.face(), Some(FaceDir::PosY));
+        assert_eq!(hit.adjacent_position(), [4, 5, 4]);
+    }
+
+    #[test]
+    fn ray_from_the_north_hits_the_north_face() {
+        let world = world_with_block();
+        // North is toward negative Z
+        let origin = Point3::new(4.5, 4.5, 1.0);
+
+        let hit = raycast(&world, origin, Vector3::new(0.0, 0.0, 1.0), 10.0, false).unwrap();
+
+        assert_eq!(hit.position, [4, 4, 4]);
+        assert_eq!(hit.face(), Some(FaceDir::NegZ));
+    }
+
+    #[test]
+    fn ray_starting_inside_a_block_has_no_face() {
+        let world = world_with_block();
+        let origin = Point3::new(4.5, 4.5, 4.5);
+
+        let hit = raycast(&world, origin, Vector3::new(1.0, 0.0, 0.0), 10.0, false).unwrap();
+
+        assert_eq!(hit.position, [4, 4, 4]);
+        assert_eq!(hit.face(), None);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::config::{Config, SaveFormat};
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE, ChunkData};
+use crate::world::generation::{Terrain, generate_chunk};
+use crate::world::world::World;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+/// The size of a full chunk file, in bytes.
+const CHUNK_FILE_SIZE: usize = CHUNK_SIZE * CHUNK_HEIGHT * CHUNK_SIZE * 4;
+
+/// The size of one edited block in a delta chunk file, in bytes: the chunk-local x, y and z
+/// coordinates followed by the block id.
+const DELTA_ENTRY_SIZE: usize = 7;
+
+/// The outcome of saving one chunk: its coordinates, the edit version that was written,
+/// and whether the write succeeded.
+type SaveResult = ((i32, i32), u64, io::Result<()>);
+
+/// Saves modified chunks to disk on a background thread.
+///
+/// Saving snapshots the dirty chunks on the main thread and writes them off-thread, so large
+/// saves don't freeze the game. A chunk's dirty flag is only cleared once its write succeeded
+/// and it hasn't been edited again since the snapshot was taken.
+pub struct WorldSaver {
+    task: Option<JoinHandle<Vec<SaveResult>>>,
+    since_last_save: f32,
+}
+
+impl WorldSaver {
+    /// Creates a new `WorldSaver` with no save in progress.
+    ///
+    /// # Returns
+    ///
+    /// A new `WorldSaver` instance.
+    pub fn new() -> Self {
+        WorldSaver {
+            task: None,
+            since_last_save: 0.0,
+        }
+    }
+
+    /// Polls the running save and starts an autosave when the interval has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A mutable reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    /// * `delta_time` - The time elapsed since the last frame.
+    pub fn update(&mut self, world: &mut World, config: &Config, delta_time: f32) {
+        if self.task.as_ref().is_some_and(|task| task.is_finished()) {
+            self.finish_task(world);
+        }
+
+        self.since_last_save += delta_time;
+        let interval = config.world.autosave_interval;
+        if interval > 0.0 && self.since_last_save >= interval && self.save_world(world, config) {
+            self.since_last_save = 0.0;
+        }
+    }
+
+    /// Starts saving all dirty chunks in the background.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a save was started, `false` if one is already in progress.
+    pub fn save_world(&mut self, world: &World, config: &Config) -> bool {
+        if self.task.is_some() {
+            return false;
+        }
+
+        let snapshots: Vec<((i32, i32), u64, ChunkData)> = world
+            .chunks
+            .iter()
+            .filter(|(_, chunk)| chunk.dirty)
+            .map(|(&coord, chunk)| (coord, chunk.version, chunk.blocks.clone()))
+            .collect();
+        if snapshots.is_empty() {
+            return true;
+        }
+
+        let directory = PathBuf::from(&world.save_directory);
+        let format = config.world.save_format;
+        let terrain = Terrain::new(world.seed, &config.world);
+        self.task = Some(thread::spawn(move || {
+            if let Err(e) = fs::create_dir_all(&directory) {
+                eprintln!("Error creating save directory: {}", e);
+            }
+
+            snapshots
+                .into_iter()
+                .map(|(coord, version, blocks)| {
+                    let result = write_chunk(&directory, coord, &blocks, format, terrain);
+                    (coord, version, result)
+                })
+                .collect()
+        }));
+
+        true
+    }
+
+    /// Saves all dirty chunks and waits for the writes to finish. Used when exiting.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A mutable reference to the game world.
+    /// * `config` - A reference to the game configuration.
+    pub fn save_world_blocking(&mut self, world: &mut World, config: &Config) {
+        // Let any autosave in flight finish first, so edits made since are saved too
+        self.finish_task(world);
+        self.save_world(world, config);
+        self.finish_task(world);
+    }
+
+    /// Waits for the running save, if any, and clears the dirty flags of the saved chunks.
+    fn finish_task(&mut self, world: &mut World) {
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        let results = match task.join() {
+            Ok(results) => results,
+            Err(_) => {
+                eprintln!("World save thread panicked; chunks will be saved again");
+                return;
+            }
+        };
+
+        for (coord, version, result) in results {
+            match result {
+                Ok(()) => {
+                    // Chunks edited again mid-save keep their dirty flag
+                    if let Some(chunk) = world.chunks.get_mut(&coord)
+                        && chunk.version == version
+                    {
+                        chunk.dirty = false;
+                    }
+                }
+                Err(e) => eprintln!("Error saving chunk {:?}: {}", coord, e),
+            }
+        }
+    }
+}
+
+/// Loads a previously saved chunk, in either save format.
+///
+/// Delta saves are applied on top of freshly generated terrain.
+///
+/// # Arguments
+///
+/// * `directory` - The directory containing the saved chunks.
+/// * `coord` - The coordinates of the chunk.
+/// * `terrain` - The terrain settings, used to regenerate the terrain of delta saves.
+///
+/// # Returns
+///
+/// The saved block data, or `None` if the chunk was never saved or its file is invalid.
+pub fn load_chunk(directory: &str, coord: (i32, i32), terrain: Terrain) -> Option<ChunkData> {
+    let directory = Path::new(directory);
+    if let Ok(bytes) = fs::read(chunk_path(directory, coord, SaveFormat::Delta)) {
+        return load_delta(&bytes, coord, terrain);
+    }
+
+    let path = chunk_path(directory, coord, SaveFormat::Full);
+    let bytes = fs::read(&path).ok()?;
+    if bytes.len() != CHUNK_FILE_SIZE {
+        eprintln!(
+            "Ignoring saved chunk {} with unexpected size {}",
+            path.display(),
+            bytes.len()
+        );
+        return None;
+    }
+
+    let mut blocks = ChunkData::new();
+    let mut values = bytes
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    for x in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_SIZE {
+                blocks.set(x, y, z, values.next()?);
+            }
+        }
+    }
+
+    Some(blocks)
+}
+
+/// Regenerates a chunk and applies the edited blocks of a delta save.
+fn load_delta(bytes: &[u8], coord: (i32, i32), terrain: Terrain) -> Option<ChunkData> {
+    if !bytes.len().is_multiple_of(DELTA_ENTRY_SIZE) {
+        eprintln!(
+            "Ignoring delta save of chunk {:?} with unexpected size {}",
+            coord,
+            bytes.len()
+        );
+        return None;
+    }
+
+    let mut blocks = generate_chunk(coord.0, coord.1, terrain);
+    for entry in bytes.chunks_exact(DELTA_ENTRY_SIZE) {
+        let (x, y, z) = (entry[0] as usize, entry[1] as usize, entry[2] as usize);
+        if x >= CHUNK_SIZE || y >= CHUNK_HEIGHT || z >= CHUNK_SIZE {
+            eprintln!(
+                "Ignoring delta save of chunk {:?} with invalid blocks",
+                coord
+            );
+            return None;
+        }
+        let block_id = u32::from_le_bytes([entry[3], entry[4], entry[5], entry[6]]);
+        blocks.set(x, y, z, block_id);
+    }
+
+    Some(blocks)
+}
+
+/// Returns the path of the file a chunk is saved to in the given format.
+fn chunk_path(directory: &Path, coord: (i32, i32), format: SaveFormat) -> PathBuf {
+    let extension = match format {
+        SaveFormat::Full => "chunk",
+        SaveFormat::Delta => "delta",
+    };
+    directory.join(format!("{}_{}.{}", coord.0, coord.1, extension))
+}
+
+/// Writes a chunk's blocks to disk, replacing any previous save of the chunk.
+fn write_chunk(
+    directory: &Path,
+    coord: (i32, i32),
+    blocks: &ChunkData,
+    format: SaveFormat,
+    terrain: Terrain,
+) -> io::Result<()> {
+    let bytes = match format {
+        SaveFormat::Full => {
+            let mut bytes = Vec::with_capacity(CHUNK_FILE_SIZE);
+            for x in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_HEIGHT {
+                    for z in 0..CHUNK_SIZE {
+                        bytes.extend_from_slice(&blocks.get(x, y, z).to_le_bytes());
+                    }
+                }
+            }
+            bytes
+        }
+        SaveFormat::Delta => {
+            // Only store the blocks the player changed from the generated terrain
+            let generated = generate_chunk(coord.0, coord.1, terrain);
+            let mut bytes = Vec::new();
+            for x in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_HEIGHT {
+                    for z in 0..CHUNK_SIZE {
+                        let block_id = blocks.get(x, y, z);
+                        if block_id != generated.get(x, y, z) {
+                            bytes.extend_from_slice(&[x as u8, y as u8, z as u8]);
+                            bytes.extend_from_slice(&block_id.to_le_bytes());
+                        }
+                    }
+                }
+            }
+            bytes
+        }
+    };
+
+    // Write to a temporary file first so a crash mid-write can't corrupt the previous save
+    let path = chunk_path(directory, coord, format);
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &bytes)?;
+    fs::rename(&temp_path, &path)?;
+
+    // Remove a save in the other format, so it can't shadow this one when loading
+    let other = match format {
+        SaveFormat::Full => SaveFormat::Delta,
+        SaveFormat::Delta => SaveFormat::Full,
+    };
+    match fs::remove_file(chunk_path(directory, coord, other)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,103 @@
+use crate::config::{Config, SpawnMode};
+use crate::world::border::WorldBorder;
+use crate::world::chunk::chunk_coords;
+use crate::world::chunk_manager::ChunkManager;
+use crate::world::generation::Terrain;
+use crate::world::world::World;
+use cgmath::Point3;
+
+/// How far from the origin a random spawn point may be, in blocks.
+const RANDOM_SPAWN_RANGE: u32 = 512;
+
+/// The gap left between the player's feet and the ground when spawning, in blocks.
+const SPAWN_CLEARANCE: f32 = 0.01;
+
+/// Finds a safe camera position to start the game at.
+///
+/// The chunk under the spawn point is loaded (or generated) right away, and the player is
+/// placed on top of the highest solid block of the spawn column, so they never start inside the
+/// terrain or in mid-air over an unloaded chunk. Spawn points outside the world border are
+/// moved inside it.
+///
+/// # Arguments
+///
+/// * `world` - A mutable reference to the game world.
+/// * `config` - A reference to the game configuration.
+///
+/// # Returns
+///
+/// The camera (eye) position of the spawned player, in world units.
+pub fn spawn_position(world: &mut World, config: &Config) -> Point3<f32> {
+    let (x, z) = match config.world.spawn {
+        SpawnMode::Fixed => {
+            let position = world.to_block_space(Point3::new(
+                config.camera.position_x,
+                config.camera.position_y,
+                config.camera.position_z,
+            ));
+            (position.x.floor() as i32, position.z.floor() as i32)
+        }
+        SpawnMode::RandomSurface => random_column(world.seed),
+    };
+    let (x, z) = match WorldBorder::new(&config.world) {
+        Some(border) => border.clamp_column(x, z),
+        None => (x, z),
+    };
+
+    // Stand on the highest block of the column, or on the configured height if that's higher
+    let position = surface_position(world, (x, z), config);
+    match config.world.spawn {
+        SpawnMode::Fixed => Point3::new(
+            position.x,
+            position.y.max(config.camera.position_y),
+            position.z,
+        ),
+        SpawnMode::RandomSurface => position,
+    }
+}
+
+/// Finds the camera position of a player standing on the highest solid block of a column.
+///
+/// The chunk of the column is loaded (or generated) right away if it isn't loaded yet.
+///
+/// # Arguments
+///
+/// * `world` - A mutable reference to the game world.
+/// * `column` - The world block x and z coordinates of the column.
+/// * `config` - A reference to the game configuration.
+///
+/// # Returns
+///
+/// The camera (eye) position above the center of the column, in world units.
+pub fn surface_position(world: &mut World, column: (i32, i32), config: &Config) -> Point3<f32> {
+    let (x, z) = column;
+    let coord = chunk_coords(x, z);
+    if !world.chunks.contains_key(&coord) {
+        ChunkManager::load_or_generate(world, coord, config);
+    }
+
+    let surface = world.surface_height(x, z, Terrain::new(world.seed, &config.world)) + 1;
+    let feet_y = (surface as f32 + SPAWN_CLEARANCE) * world.block_size;
+    Point3::new(
+        (x as f32 + 0.5) * world.block_size,
+        feet_y + config.physics.eye_height,
+        (z as f32 + 0.5) * world.block_size,
+    )
+}
+
+/// Picks the spawn column for a seed, within `RANDOM_SPAWN_RANGE` of the origin.
+fn random_column(seed: u32) -> (i32, i32) {
+    // Mix the seed so neighboring seeds don't spawn next to each other
+    let mut hash = seed.wrapping_mul(0x9E37_79B9) ^ 0x85EB_CA6B;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7FEB_352D);
+    hash ^= hash >> 15;
+
+    let span = RANDOM_SPAWN_RANGE * 2;
+    let x = (hash & 0xFFFF) % span;
+    let z = (hash >> 16) % span;
+    (
+        x as i32 - RANDOM_SPAWN_RANGE as i32,
+        z as i32 - RANDOM_SPAWN_RANGE as i32,
+    )
+}
@@ -0,0 +1,341 @@
+use crate::config::Config;
+use crate::world::block::{Block, MAX_WATER_LEVEL};
+use crate::world::generation::Rng;
+use crate::world::world::World;
+use std::collections::{HashMap, HashSet};
+
+/// The time between two block ticks, in seconds (20 ticks per second).
+const TICK_INTERVAL: f32 = 0.05;
+
+/// The most ticks run in one frame, so a long frame doesn't stall the game catching up.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// The chance per tick that dirt next to grass turns into grass.
+const GRASS_SPREAD_CHANCE: f32 = 0.02;
+
+/// The offsets of the four blocks sharing a side face with a block.
+const SIDE_NEIGHBORS: [[i32; 2]; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
+
+/// Runs periodic updates for blocks that change over time, on a fixed timestep.
+///
+/// Only registered ("active") positions are ticked. A block stays active while its tick
+/// returns `true`; blocks changed through `World::set_block` wake up themselves and their
+/// surrounding blocks, so new dynamic blocks don't need to be registered by hand.
+pub struct TickingBlocks {
+    active: HashSet<[i32; 3]>,
+    accumulator: f32,
+    water_progress: f32,
+    /// How long each leaf block without a nearby log has been decaying, in seconds.
+    leaf_decay: HashMap<[i32; 3], f32>,
+    changes: Vec<([i32; 3], u32)>,
+    rng: Rng,
+}
+
+impl TickingBlocks {
+    /// Creates a new `TickingBlocks` with no active blocks.
+    ///
+    /// # Returns
+    ///
+    /// A new `TickingBlocks` instance.
+    pub fn new() -> Self {
+        TickingBlocks {
+            active: HashSet::new(),
+            accumulator: 0.0,
+            water_progress: 0.0,
+            leaf_decay: HashMap::new(),
+            changes: Vec::new(),
+            rng: Rng::new(0x2545_F491),
+        }
+    }
+
+    /// Registers a block position to be ticked.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The world block coordinates of the block.
+    pub fn register(&mut self, position: [i32; 3]) {
+        self.active.insert(position);
+    }
+
+    /// Stops ticking a block position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The world block coordinates of the block.
+    pub fn unregister(&mut self, position: [i32; 3]) {
+        self.active.remove(&position);
+    }
+
+    /// Returns the number of blocks that are currently ticked.
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Runs the block ticks that are due since the last frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - A mutable reference to the game world.
+    /// * `delta_time` - The time elapsed since the last frame.
+    /// * `config` - A reference to the game configuration.
+    pub fn update(&mut self, world: &mut World, delta_time: f32, config: &Config) {
+        self.accumulator += delta_time;
+
+        let mut ticks = 0;
+        while self.accumulator >= TICK_INTERVAL {
+            self.accumulator -= TICK_INTERVAL;
+            ticks += 1;
+            if ticks > MAX_TICKS_PER_FRAME {
+                // Drop the backlog instead of falling further behind
+                self.accumulator = 0.0;
+                break;
+            }
+
+            // Water only flows on some ticks, depending on the flow speed
+            self.water_progress += config.world.water_flow_speed.max(0.0) * TICK_INTERVAL;
+            let water_step = self.water_progress >= 1.0;
+            if water_step {
+                self.water_progress = (self.water_progress - 1.0).min(1.0);
+            }
+            self.tick(world, water_step, config);
+        }
+    }
+
+    /// Runs one tick of every active block.
+    ///
+    /// All blocks see the world as it was at the start of the tick; their changes are
+    /// applied afterwards. Blocks are ticked in a fixed order, and when several blocks change
+    /// the same cell, such as water spreading into it from two sides, the fullest water
+    /// wins, so the result doesn't depend on how the active blocks are stored.
+    fn tick(&mut self, world: &mut World, water_step: bool, config: &Config) {
+        // Wake up changed blocks and every block around them, including diagonals along slopes
+        let updates: Vec<[i32; 3]> = world.block_updates.drain().collect();
+        for position in updates {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        self.register(add(position, [dx, dy, dz]));
+                    }
+                }
+            }
+
+            // A removed block may have been the log that kept leaves further away alive
+            let [x, y, z] = position;
+            if world.get_block(x, y, z) == 0 {
+                self.wake_leaves(world, position, config.world.leaf_decay_radius);
+            }
+        }
+
+        let mut positions: Vec<[i32; 3]> = self.active.iter().copied().collect();
+        positions.sort_unstable();
+        for position in positions {
+            if !self.tick_block(world, position, water_step, config) {
+                self.unregister(position);
+            }
+        }
+
+        let mut resolved: HashMap<[i32; 3], u32> = HashMap::new();
+        for (position, block_id) in self.changes.drain(..) {
+            resolved
+                .entry(position)
+                .and_modify(|current| {
+                    if change_priority(block_id) > change_priority(*current) {
+                        *current = block_id;
+                    }
+                })
+                .or_insert(block_id);
+        }
+
+        // Setting a block wakes up its surroundings, so changes keep propagating
+        for ([x, y, z], block_id) in resolved {
+            world.set_block(x, y, z, block_id);
+        }
+    }
+
+    /// Updates a single block.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the block should keep ticking.
+    fn tick_block(
+        &mut self,
+        world: &World,
+        position: [i32; 3],
+        water_step: bool,
+        config: &Config,
+    ) -> bool {
+        let [x, y, z] = position;
+        let block = Block::new(world.get_block(x, y, z));
+        if block.id != 20 {
+            self.leaf_decay.remove(&position);
+        }
+        match block.id {
+            1 => self.spread_grass(world, position),
+            20 => self.decay_leaves(world, position, config),
+            _ if block.water_level() > 0 => !water_step || self.flow_water(world, position),
+            _ => false,
+        }
+    }
+
+    /// Registers every leaf block within the decay radius of a position.
+    fn wake_leaves(&mut self, world: &World, position: [i32; 3], radius: i32) {
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                for dz in -radius..=radius {
+                    let [x, y, z] = add(position, [dx, dy, dz]);
+                    if world.get_block(x, y, z) == 20 {
+                        self.register([x, y, z]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes leaves that have been without a nearby log for the decay time.
+    ///
+    /// Removing a leaf wakes up the leaves around it, so whole orphaned canopies decay.
+    ///
+    /// # Returns
+    ///
+    /// `true` while the leaves are decaying, `false` once they are supported or removed.
+    fn decay_leaves(&mut self, world: &World, position: [i32; 3], config: &Config) -> bool {
+        let radius = config.world.leaf_decay_radius;
+        let supported = (-radius..=radius).any(|dx| {
+            (-radius..=radius).any(|dy| {
+                (-radius..=radius).any(|dz| {
+                    let [x, y, z] = add(position, [dx, dy, dz]);
+                    Block::new(world.get_block(x, y, z)).is_log()
+                })
+            })
+        });
+        if supported {
+            self.leaf_decay.remove(&position);
+            return false;
+        }
+
+        let decay = self.leaf_decay.entry(position).or_insert(0.0);
+        *decay += TICK_INTERVAL;
+        if *decay >= config.world.leaf_decay_time {
+            self.leaf_decay.remove(&position);
+            self.changes.push((position, 0));
+            return false;
+        }
+        true
+    }
+
+    /// Turns dirt into grass over time if it is uncovered and next to grass.
+    ///
+    /// # Returns
+    ///
+    /// `true` while the dirt can still turn into grass.
+    fn spread_grass(&mut self, world: &World, position: [i32; 3]) -> bool {
+        let [x, y, z] = position;
+        if world.get_block(x, y + 1, z) != 0 {
+            return false;
+        }
+
+        // Grass spreads sideways, and up or down one block along slopes
+        let next_to_grass = (-1..=1).any(|dy| {
+            SIDE_NEIGHBORS
+                .iter()
+                .any(|offset| world.get_block(x + offset[0], y + dy, z + offset[1]) == 2)
+        });
+        if !next_to_grass {
+            return false;
+        }
+
+        if self.rng.next_f32() < GRASS_SPREAD_CHANCE {
+            self.changes.push((position, 2));
+            return false;
+        }
+        true
+    }
+
+    /// Updates the level of a water block and spreads it into the empty cells around it.
+    ///
+    /// Water falls into empty cells below at almost full level, and otherwise spreads
+    /// sideways one level lower per block. Flowing water takes its level from the highest
+    /// water around it, so it dries up once its source is removed. Sources never change.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the water will keep changing, `false` once it has settled.
+    fn flow_water(&mut self, world: &World, position: [i32; 3]) -> bool {
+        let [x, y, z] = position;
+        let level_at = |dx: i32, dy: i32, dz: i32| {
+            Block::new(world.get_block(x + dx, y + dy, z + dz)).water_level()
+        };
+
+        let mut level = level_at(0, 0, 0);
+        if level < MAX_WATER_LEVEL {
+            let fed = if level_at(0, 1, 0) > 0 {
+                MAX_WATER_LEVEL - 1
+            } else {
+                SIDE_NEIGHBORS
+                    .iter()
+                    .map(|offset| level_at(offset[0], 0, offset[1]))
+                    .max()
+                    .unwrap_or(0)
+                    .saturating_sub(1)
+            };
+
+            if fed != level {
+                level = fed;
+                let block_id = if level == 0 { 0 } else { Block::water(level) };
+                self.changes.push((position, block_id));
+                return true;
+            }
+        }
+
+        // Fall down first, and only spread sideways on top of something
+        if y > 0 && world.get_block(x, y - 1, z) == 0 {
+            self.changes
+                .push(([x, y - 1, z], Block::water(MAX_WATER_LEVEL - 1)));
+            return true;
+        }
+        if world.get_block(x, y - 1, z) != 0 && level_at(0, -1, 0) == 0 && level > 1 {
+            for offset in SIDE_NEIGHBORS {
+                let (nx, nz) = (x + offset[0], z + offset[1]);
+                if world.get_block(nx, y, nz) == 0 {
+                    self.changes.push(([nx, y, nz], Block::water(level - 1)));
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Returns the rank of a block change among changes to the same cell; the highest wins.
+///
+/// Fuller water wins over lower water and air, and other ties go to the higher block id.
+fn change_priority(block_id: u32) -> (u8, u32) {
+    (Block::new(block_id).water_level(), block_id)
+}
+
+/// Returns the sum of two block positions.
+fn add(a: [i32; 3], b: [i32; 3]) -> [i32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fullest_water_wins_a_contested_cell() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        for x in 0..4 {
+            world.set_block(x, 0, 0, 3);
+        }
+        // Level 7 water fed by a source spreads level 6 into x = 2; the source at x = 3
+        // spreads level 7 into the same cell
+        world.set_block(0, 1, 0, Block::water(MAX_WATER_LEVEL));
+        world.set_block(1, 1, 0, Block::water(MAX_WATER_LEVEL - 1));
+        world.set_block(3, 1, 0, Block::water(MAX_WATER_LEVEL));
+
+        let mut ticking = TickingBlocks::new();
+        ticking.tick(&mut world, true, &Config::default());
+
+        assert_eq!(world.get_block(2, 1, 0), Block::water(MAX_WATER_LEVEL - 1));
+    }
+}
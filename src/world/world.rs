@@ -1,45 +1,337 @@
 extern crate gl;
 
+use crate::config::{GraphicsConfig, WorldConfig};
 use crate::rendering::mesh::Mesh;
 use crate::rendering::shader::Shader;
-use crate::world::cube_render::draw_chunk;
-use crate::world::generation::generate_chunk;
-use cgmath::Point3;
+use crate::world::block::{Block, BlockBox};
+use crate::world::chunk::{CHUNK_HEIGHT, CHUNK_SIZE, Chunk, chunk_coords, local_coords};
+use crate::world::cube_render::draw_block_edges;
+use crate::world::generation::{Terrain, height_at};
+use crate::world::mirror::MirrorPlane;
+use cgmath::{Matrix4, Point3, Vector3};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 
-/// Represents the game world, including blocks and rendering.
+/// The highest ledge, such as a slab, that is stepped onto without jumping, in blocks.
+const MAX_STEP_HEIGHT: f32 = 0.5;
+
+/// Represents the game world, including the loaded chunks and rendering.
 pub struct World {
-    pub chunk_data: [[[u32; 16]; 16]; 16],
+    pub chunks: HashMap<(i32, i32), Chunk>,
     pub mesh: Mesh,
+    /// The edge length of a block in world units.
+    pub block_size: f32,
+    /// The seed the terrain is generated from. Starts as the configured seed and changes
+    /// when the world is regenerated, without touching the configuration.
+    pub seed: u32,
+    /// The directory the chunks of this world are saved in.
+    pub save_directory: String,
+    /// Loaded chunks whose light hasn't been computed yet.
+    pub light_updates: HashSet<(i32, i32)>,
+    /// Blocks changed since their light was last updated, in the order they were changed.
+    pub block_light_updates: Vec<[i32; 3]>,
+    /// Chunks whose mesh is out of date, collected during the frame so a chunk edited many
+    /// times (such as by the fill tool) is only re-meshed once.
+    pub mesh_updates: HashSet<(i32, i32)>,
+    /// Blocks changed since the ticking blocks last looked at their neighborhood.
+    pub block_updates: HashSet<[i32; 3]>,
+    /// The plane that player edits are mirrored across, if the mirror tool is on.
+    pub mirror: Option<MirrorPlane>,
+    /// Set once the edge budget warning was logged, so it isn't repeated every frame.
+    edge_budget_warned: Cell<bool>,
 }
 
 impl World {
-    /// Creates a new `World` instance with the given mesh.
+    /// Creates a new, empty `World` instance with the given mesh.
+    ///
+    /// Chunks are loaded around the player by the `ChunkManager`.
     ///
     /// # Arguments
     ///
-    /// * `mesh` - The mesh to use for rendering the world.
+    /// * `mesh` - The cube mesh used for rendering block edges.
+    /// * `config` - The world settings, for the block size, seed and save directory.
     ///
     /// # Returns
     ///
     /// A new `World` instance.
-    pub fn new(mesh: Mesh) -> Self {
+    pub fn new(mesh: Mesh, config: &WorldConfig) -> Self {
         World {
-            chunk_data: generate_chunk(),
+            chunks: HashMap::new(),
             mesh,
+            block_size: config.block_size,
+            seed: config.seed,
+            save_directory: config.save_directory.clone(),
+            light_updates: HashSet::new(),
+            block_light_updates: Vec::new(),
+            mesh_updates: HashSet::new(),
+            block_updates: HashSet::new(),
+            mirror: None,
+            edge_budget_warned: Cell::new(false),
+        }
+    }
+
+    /// Creates a world of unit blocks with empty chunks at the given coordinates, for tests.
+    #[cfg(test)]
+    pub fn with_empty_chunks(coords: &[(i32, i32)]) -> Self {
+        let config = WorldConfig {
+            block_size: 1.0,
+            ..WorldConfig::default()
+        };
+        let mut world = World::new(Mesh::empty(), &config);
+        for &coord in coords {
+            world
+                .chunks
+                .insert(coord, Chunk::new(crate::world::chunk::ChunkData::new()));
         }
+        world
+    }
+
+    /// Unloads every chunk and forgets all queued work, such as before generating a new world.
+    ///
+    /// The block size, seed, save directory, cube mesh and mirror plane are kept. Chunks with
+    /// unsaved edits are dropped too, so the world should be saved first.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.light_updates.clear();
+        self.block_light_updates.clear();
+        self.mesh_updates.clear();
+        self.block_updates.clear();
+        self.edge_budget_warned.set(false);
+    }
+
+    /// Converts a world-space position into block space, where each block spans one unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position in world units.
+    ///
+    /// # Returns
+    ///
+    /// The position in blocks.
+    pub fn to_block_space(&self, position: Point3<f32>) -> Point3<f32> {
+        position / self.block_size
+    }
+
+    /// Returns the block id at the given world block coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world block x coordinate.
+    /// * `y` - The world block y coordinate.
+    /// * `z` - The world block z coordinate.
+    ///
+    /// # Returns
+    ///
+    /// The block id, or `0` (air) outside the world height or in unloaded chunks.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> u32 {
+        if y < 0 || y >= CHUNK_HEIGHT as i32 {
+            return 0;
+        }
+
+        match self.chunks.get(&chunk_coords(x, z)) {
+            Some(chunk) => {
+                let (local_x, local_z) = local_coords(x, z);
+                chunk.get_block(local_x, y as usize, local_z)
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the block light level at the given world block coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world block x coordinate.
+    /// * `y` - The world block y coordinate.
+    /// * `z` - The world block z coordinate.
+    ///
+    /// # Returns
+    ///
+    /// The light level, or `0` outside the world height or in unloaded chunks.
+    pub fn get_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        if y < 0 || y >= CHUNK_HEIGHT as i32 {
+            return 0;
+        }
+
+        match self.chunks.get(&chunk_coords(x, z)) {
+            Some(chunk) => {
+                let (local_x, local_z) = local_coords(x, z);
+                chunk.light[local_x][y as usize][local_z]
+            }
+            None => 0,
+        }
+    }
+
+    /// Queues a chunk to be re-meshed, if it is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The coordinates of the chunk.
+    pub fn queue_mesh(&mut self, coord: (i32, i32)) {
+        if self.chunks.contains_key(&coord) {
+            self.mesh_updates.insert(coord);
+        }
+    }
+
+    /// Returns the height of the terrain surface at a column.
+    ///
+    /// Loaded chunks are scanned from the top down for the first solid block, so edits are
+    /// taken into account. Columns in chunks that aren't loaded fall back to the generated
+    /// terrain height.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world block x coordinate of the column.
+    /// * `z` - The world block z coordinate of the column.
+    /// * `terrain` - The terrain settings, used for columns that aren't loaded.
+    ///
+    /// # Returns
+    ///
+    /// The y coordinate of the highest solid block, or `-1` if a loaded column has none.
+    pub fn surface_height(&self, x: i32, z: i32, terrain: Terrain) -> i32 {
+        if !self.chunks.contains_key(&chunk_coords(x, z)) {
+            return height_at(x, z, terrain) as i32;
+        }
+
+        (0..CHUNK_HEIGHT as i32)
+            .rev()
+            .find(|&y| Block::new(self.get_block(x, y, z)).is_solid())
+            .unwrap_or(-1)
+    }
+
+    /// Sets the block id at the given world block coordinates.
+    ///
+    /// Each cell holds exactly one block, so this replaces whatever was there. The containing
+    /// chunk is re-meshed, along with any neighboring chunk that shares the edited block's
+    /// faces. The light around the block is updated before the next re-mesh, spreading into
+    /// neighboring chunks as far as it reaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world block x coordinate.
+    /// * `y` - The world block y coordinate.
+    /// * `z` - The world block z coordinate.
+    /// * `block_id` - The id of the new block (`0` for air).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the block was set, `false` if the position is outside the loaded world.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block_id: u32) -> bool {
+        if y < 0 || y >= CHUNK_HEIGHT as i32 {
+            return false;
+        }
+
+        let (chunk_x, chunk_z) = chunk_coords(x, z);
+        let (local_x, local_z) = local_coords(x, z);
+        match self.chunks.get_mut(&(chunk_x, chunk_z)) {
+            Some(chunk) => chunk.set_block(local_x, y as usize, local_z, block_id),
+            None => return false,
+        }
+        self.block_light_updates.push([x, y, z]);
+        self.mesh_updates.insert((chunk_x, chunk_z));
+        self.block_updates.insert([x, y, z]);
+
+        // Edits on a chunk border change the faces of the neighboring chunk too
+        let mut neighbors = Vec::new();
+        if local_x == 0 {
+            neighbors.push((chunk_x - 1, chunk_z));
+        } else if local_x == CHUNK_SIZE - 1 {
+            neighbors.push((chunk_x + 1, chunk_z));
+        }
+        if local_z == 0 {
+            neighbors.push((chunk_x, chunk_z - 1));
+        } else if local_z == CHUNK_SIZE - 1 {
+            neighbors.push((chunk_x, chunk_z + 1));
+        }
+        for coord in neighbors {
+            self.queue_mesh(coord);
+        }
+
+        true
+    }
+
+    /// Sets a block on behalf of the player, also setting its mirror image if the mirror
+    /// tool is on.
+    ///
+    /// Blocks on the mirror plane are their own mirror image and are only set once.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The world block x coordinate.
+    /// * `y` - The world block y coordinate.
+    /// * `z` - The world block z coordinate.
+    /// * `block_id` - The id of the new block (`0` for air).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the block itself was set, `false` if its position is outside the loaded
+    /// world.
+    pub fn edit_block(&mut self, x: i32, y: i32, z: i32, block_id: u32) -> bool {
+        if let Some([mirror_x, mirror_y, mirror_z]) =
+            self.mirror.and_then(|plane| plane.mirror([x, y, z]))
+        {
+            self.set_block(mirror_x, mirror_y, mirror_z, block_id);
+        }
+        self.set_block(x, y, z, block_id)
+    }
+
+    /// Places a block on behalf of the player if its cell is empty, also placing its mirror
+    /// image if the mirror tool is on.
+    ///
+    /// Several placements of the same cell, such as from clicks handled in the same frame,
+    /// leave the first block in place instead of replacing it. The mirror image is only
+    /// placed where it fits: a mirrored cell that is occupied or overlaps the player is
+    /// skipped, while the block itself is still placed.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The world block coordinates of the cell.
+    /// * `block_id` - The id of the block to place.
+    /// * `feet` - The position of the player's feet, in world units.
+    /// * `radius` - The horizontal radius of the player's collision box, in world units.
+    /// * `height` - The height of the player's collision box, in world units.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the block was placed, `false` if the cell is occupied or outside the
+    /// loaded world.
+    pub fn place_block(
+        &mut self,
+        position: [i32; 3],
+        block_id: u32,
+        feet: &Point3<f32>,
+        radius: f32,
+        height: f32,
+    ) -> bool {
+        let [x, y, z] = position;
+        if self.get_block(x, y, z) != 0 {
+            return false;
+        }
+
+        if let Some(mirrored) = self.mirror.and_then(|plane| plane.mirror(position))
+            && self.get_block(mirrored[0], mirrored[1], mirrored[2]) == 0
+            && !self.check_block_collision(feet, mirrored, &BlockBox::FULL, radius, height)
+        {
+            self.set_block(mirrored[0], mirrored[1], mirrored[2], block_id);
+        }
+        self.set_block(x, y, z, block_id)
     }
 
     /// Resolves collision and returns a safe position
+    ///
+    /// The positions are camera (eye) positions, which sit `eye_height` above the feet of a
+    /// collision box `height` tall.
     pub fn resolve_collision(
         &self,
         current_pos: Point3<f32>,
         target_pos: Point3<f32>,
         radius: f32,
         height: f32,
+        eye_height: f32,
     ) -> Point3<f32> {
         // Convert camera position (eyes) to feet position for collision
-        let feet_current = Point3::new(current_pos.x, current_pos.y - height, current_pos.z);
-        let feet_target = Point3::new(target_pos.x, target_pos.y - height, target_pos.z);
+        let feet_current = Point3::new(current_pos.x, current_pos.y - eye_height, current_pos.z);
+        let feet_target = Point3::new(target_pos.x, target_pos.y - eye_height, target_pos.z);
 
         // If no collision at target position, return it
         if !self.check_collision(&feet_target, radius, height) {
@@ -61,6 +353,27 @@ impl World {
             new_feet_pos.z = feet_target.z;
         }
 
+        // Step up onto low ledges such as slabs while standing on the ground, if the way
+        // forward is blocked at the current height but free just above it
+        let blocked = new_feet_pos.x != feet_target.x || new_feet_pos.z != feet_target.z;
+        if blocked {
+            let below = Point3::new(
+                feet_current.x,
+                feet_current.y - 0.05 * self.block_size,
+                feet_current.z,
+            );
+            let stepped = Point3::new(
+                feet_target.x,
+                feet_current.y + MAX_STEP_HEIGHT * self.block_size,
+                feet_target.z,
+            );
+            if self.check_collision(&below, radius, 0.1 * self.block_size)
+                && !self.check_collision(&stepped, radius, height)
+            {
+                new_feet_pos = stepped;
+            }
+        }
+
         // Try vertical movement (for jumping/falling)
         let y_pos = Point3::new(new_feet_pos.x, feet_target.y, new_feet_pos.z);
         if !self.check_collision(&y_pos, radius, height) {
@@ -68,28 +381,38 @@ impl World {
         }
 
         // Convert back to camera/eye position
-        Point3::new(new_feet_pos.x, new_feet_pos.y + height, new_feet_pos.z)
+        Point3::new(new_feet_pos.x, new_feet_pos.y + eye_height, new_feet_pos.z)
     }
 
+    /// Checks whether a player cylinder at the given feet position overlaps any solid block.
+    ///
+    /// Partial blocks such as slabs only collide where their shape boxes are, so the player
+    /// can stand on a slab at half height.
     pub fn check_collision(&self, feet_position: &Point3<f32>, radius: f32, height: f32) -> bool {
         // Convert feet position to block coordinates
-        let block_x = feet_position.x.floor() as usize;
-        let block_y = feet_position.y.floor() as usize;
-        let block_z = feet_position.z.floor() as usize;
+        let feet_block = self.to_block_space(*feet_position);
+        let block_x = feet_block.x.floor() as i32;
+        let block_y = feet_block.y.floor() as i32;
+        let block_z = feet_block.z.floor() as i32;
+        let top_y = ((feet_position.y + height) / self.block_size).floor() as i32;
 
         // Check blocks from feet position upward
-        let radius_check = radius.ceil() as usize + 1;
+        let radius_check = (radius / self.block_size).ceil() as i32 + 1;
 
-        for x in block_x.saturating_sub(radius_check)..=(block_x + radius_check).min(15) {
-            for y in block_y..=(block_y + height as usize).min(15) {
-                for z in block_z.saturating_sub(radius_check)..=(block_z + radius_check).min(15) {
-                    // Skip air blocks
-                    if self.chunk_data[x][y][z] == 0 {
+        for x in (block_x - radius_check)..=(block_x + radius_check) {
+            for y in block_y..=top_y {
+                for z in (block_z - radius_check)..=(block_z + radius_check) {
+                    // Skip air and blocks the player walks through
+                    let block = Block::new(self.get_block(x, y, z));
+                    if !block.is_solid() {
                         continue;
                     }
 
-                    // Check collision with this block
-                    if self.check_block_collision(feet_position, x, y, z, radius, height) {
+                    // Check collision with the actual shape of the block, such as a slab
+                    let collides = block.collision_boxes().iter().any(|shape| {
+                        self.check_block_collision(feet_position, [x, y, z], shape, radius, height)
+                    });
+                    if collides {
                         return true;
                     }
                 }
@@ -99,22 +422,26 @@ impl World {
         false
     }
 
-    fn check_block_collision(
+    /// Checks whether the player cylinder at the given feet position overlaps part of a block.
+    ///
+    /// The feet position, radius and height are in world units; the block is given by its
+    /// block coordinates, and `shape` is the box of the block to test, within `[0, 1]`.
+    pub fn check_block_collision(
         &self,
         feet_position: &Point3<f32>,
-        block_x: usize,
-        block_y: usize,
-        block_z: usize,
+        [block_x, block_y, block_z]: [i32; 3],
+        shape: &BlockBox,
         radius: f32,
         height: f32,
     ) -> bool {
-        // Block min/max coordinates
-        let block_min_x = block_x as f32;
-        let block_max_x = block_x as f32 + 1.0;
-        let block_min_y = block_y as f32;
-        let block_max_y = block_y as f32 + 1.0;
-        let block_min_z = block_z as f32;
-        let block_max_z = block_z as f32 + 1.0;
+        // Box min/max coordinates in world units
+        let size = self.block_size;
+        let block_min_x = (block_x as f32 + shape.min[0]) * size;
+        let block_max_x = (block_x as f32 + shape.max[0]) * size;
+        let block_min_y = (block_y as f32 + shape.min[1]) * size;
+        let block_max_y = (block_y as f32 + shape.max[1]) * size;
+        let block_min_z = (block_z as f32 + shape.min[2]) * size;
+        let block_max_z = (block_z as f32 + shape.max[2]) * size;
 
         // Player capsule min/max (from feet position)
         let player_min_y = feet_position.y;
@@ -138,13 +465,237 @@ impl World {
         distance_squared < (radius * radius)
     }
 
-    /// Draws the world using the given shader.
+    /// Draws the world using the given shaders.
     ///
     /// # Arguments
     ///
-    /// * `shader` - The shader to use for rendering.
+    /// * `chunk_shader` - The shader to use for rendering chunk meshes.
+    /// * `shader` - The shader to use for rendering block edges.
     /// * `time` - The current time (used for animations).
-    pub fn draw(&self, shader: &Shader, time: f32) {
-        draw_chunk(&self.chunk_data, &self.mesh, shader, time);
+    /// * `camera_position` - The camera position, so edges near the player are drawn first.
+    /// * `graphics` - The graphics settings for block outlines.
+    pub fn draw(
+        &self,
+        chunk_shader: &Shader,
+        shader: &Shader,
+        _time: f32,
+        camera_position: Point3<f32>,
+        graphics: &GraphicsConfig,
+    ) {
+        // First pass: Draw the chunk meshes, with block boundaries darkened by the shader
+        chunk_shader.use_program();
+        chunk_shader.set_float("outlineStrength", graphics.outline_strength);
+        for (&(chunk_x, chunk_z), chunk) in &self.chunks {
+            if let Some(mesh) = &chunk.mesh {
+                chunk_shader.set_mat4("model", &self.chunk_model(chunk_x, chunk_z));
+                mesh.draw();
+            }
+        }
+
+        // Then the translucent faces, farthest chunks first so nearer glass blends over them.
+        // They test against the depth buffer but don't write to it, so glass behind glass
+        // in the same chunk still shows
+        let camera_block = self.to_block_space(camera_position);
+        let mut translucent: Vec<(&(i32, i32), &Chunk)> = self
+            .chunks
+            .iter()
+            .filter(|(_, chunk)| chunk.translucent_mesh.is_some())
+            .collect();
+        translucent.sort_by(|(a, _), (b, _)| {
+            let distance = |coord: &(i32, i32)| {
+                let center_x = (coord.0 as f32 + 0.5) * CHUNK_SIZE as f32 - camera_block.x;
+                let center_z = (coord.1 as f32 + 0.5) * CHUNK_SIZE as f32 - camera_block.z;
+                center_x * center_x + center_z * center_z
+            };
+            distance(b).total_cmp(&distance(a))
+        });
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+        }
+        for (&(chunk_x, chunk_z), chunk) in translucent {
+            if let Some(mesh) = &chunk.translucent_mesh {
+                chunk_shader.set_mat4("model", &self.chunk_model(chunk_x, chunk_z));
+                mesh.draw();
+            }
+        }
+        unsafe {
+            gl::DepthMask(gl::TRUE);
+            gl::Disable(gl::BLEND);
+        }
+
+        // Other geometry drawn with the chunk shader isn't made of blocks
+        chunk_shader.set_float("outlineStrength", 0.0);
+
+        if !graphics.legacy_block_edges {
+            return;
+        }
+        let edge_budget = graphics.max_edge_blocks;
+
+        // Second pass: Draw legacy wireframe edges, nearest chunks first. This pass issues a
+        // draw call per block, so it stops at the edge budget instead of stalling on huge worlds
+        shader.use_program();
+        let camera_chunk =
+            chunk_coords(camera_block.x.floor() as i32, camera_block.z.floor() as i32);
+        let mut chunks: Vec<(&(i32, i32), &Chunk)> = self.chunks.iter().collect();
+        chunks.sort_by_key(|(coord, _)| {
+            (coord.0 - camera_chunk.0)
+                .abs()
+                .max((coord.1 - camera_chunk.1).abs())
+        });
+
+        let mut remaining = edge_budget;
+        for (&(chunk_x, chunk_z), chunk) in chunks {
+            let origin = Vector3::new(
+                (chunk_x * CHUNK_SIZE as i32) as f32,
+                0.0,
+                (chunk_z * CHUNK_SIZE as i32) as f32,
+            );
+            remaining -= draw_block_edges(
+                chunk,
+                origin,
+                self.block_size,
+                &self.mesh,
+                shader,
+                remaining,
+            );
+
+            if remaining == 0 {
+                if !self.edge_budget_warned.replace(true) {
+                    eprintln!(
+                        "Block edge budget of {} blocks reached; distant edges are skipped",
+                        edge_budget
+                    );
+                }
+                break;
+            }
+        }
+    }
+
+    /// Draws the chunk meshes near a position with the given depth-only shader.
+    ///
+    /// # Arguments
+    ///
+    /// * `shader` - The shader to use for rendering, with its light-space matrix set.
+    /// * `center` - The center of the shadowed area, in world units.
+    /// * `distance` - The half-width of the shadowed area, in world units.
+    pub fn draw_shadow_casters(&self, shader: &Shader, center: Point3<f32>, distance: f32) {
+        // Skip chunks that can't reach into the shadowed area
+        let chunk_extent = CHUNK_SIZE as f32 * self.block_size;
+        for (&(chunk_x, chunk_z), chunk) in &self.chunks {
+            let Some(mesh) = &chunk.mesh else {
+                continue;
+            };
+            let min_x = chunk_x as f32 * chunk_extent;
+            let min_z = chunk_z as f32 * chunk_extent;
+            if center.x < min_x - distance
+                || center.x > min_x + chunk_extent + distance
+                || center.z < min_z - distance
+                || center.z > min_z + chunk_extent + distance
+            {
+                continue;
+            }
+
+            shader.set_mat4("model", &self.chunk_model(chunk_x, chunk_z));
+            mesh.draw();
+        }
+    }
+
+    /// Returns the model matrix placing a chunk mesh at its world position.
+    fn chunk_model(&self, chunk_x: i32, chunk_z: i32) -> Matrix4<f32> {
+        self.block_model(Vector3::new(
+            (chunk_x * CHUNK_SIZE as i32) as f32,
+            0.0,
+            (chunk_z * CHUNK_SIZE as i32) as f32,
+        ))
+    }
+
+    /// Returns the model matrix for geometry positioned in block space.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The translation of the geometry, in blocks.
+    ///
+    /// # Returns
+    ///
+    /// A matrix translating by `offset` and scaling blocks to world units.
+    pub fn block_model(&self, offset: Vector3<f32>) -> Matrix4<f32> {
+        Matrix4::from_scale(self.block_size) * Matrix4::from_translation(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::mirror::MirrorAxis;
+
+    /// Returns the number of non-air blocks in the loaded chunks.
+    fn block_count(world: &World) -> usize {
+        world
+            .chunks
+            .values()
+            .map(|chunk| {
+                let mut count = 0;
+                for x in 0..CHUNK_SIZE {
+                    for y in 0..CHUNK_HEIGHT {
+                        for z in 0..CHUNK_SIZE {
+                            count += usize::from(chunk.get_block(x, y, z) != 0);
+                        }
+                    }
+                }
+                count
+            })
+            .sum()
+    }
+
+    #[test]
+    fn placing_twice_leaves_one_block() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+
+        let feet = Point3::new(10.5, 8.0, 10.5);
+
+        assert!(world.place_block([4, 8, 4], 3, &feet, 0.3, 1.8));
+        assert!(!world.place_block([4, 8, 4], 1, &feet, 0.3, 1.8));
+        assert_eq!(world.get_block(4, 8, 4), 3);
+        assert_eq!(block_count(&world), 1);
+    }
+
+    #[test]
+    fn mirrored_placement_skips_occupied_cells_and_the_player() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        world.mirror = Some(MirrorPlane {
+            axis: MirrorAxis::X,
+            coordinate: 8,
+        });
+        // The player stands in the mirror image of x = 4, and x = 14 mirrors onto stone
+        let feet = Point3::new(12.5, 8.0, 4.5);
+        world.set_block(2, 8, 4, 1);
+
+        assert!(world.place_block([4, 8, 4], 3, &feet, 0.3, 1.8));
+        assert_eq!(world.get_block(12, 8, 4), 0);
+        assert!(world.place_block([14, 8, 4], 3, &feet, 0.3, 1.8));
+        assert_eq!(world.get_block(2, 8, 4), 1);
+        assert!(world.place_block([6, 8, 6], 3, &feet, 0.3, 1.8));
+        assert_eq!(world.get_block(10, 8, 6), 3);
+    }
+
+    #[test]
+    fn walking_onto_a_slab_steps_up_to_its_top() {
+        let mut world = World::with_empty_chunks(&[(0, 0)]);
+        for x in 0..16 {
+            world.set_block(x, 0, 5, 3);
+        }
+        world.set_block(8, 1, 5, 9);
+
+        // Walk toward the slab while gravity pulls down, with the eyes 1.6 above the feet
+        let mut eyes = Point3::new(7.0, 2.6, 5.5);
+        for _ in 0..15 {
+            let target = eyes + Vector3::new(0.1, -0.05, 0.0);
+            eyes = world.resolve_collision(eyes, target, 0.3, 1.8, 1.6);
+        }
+
+        assert!(eyes.x > 8.0);
+        assert!((eyes.y - 1.6 - 1.5).abs() < 1e-4);
     }
 }